@@ -0,0 +1,371 @@
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::is_network_error;
+use crate::models::{Client, DateRange, Project, ResultWithDefaultError, Tag, TimeEntry, User};
+
+use super::client::ApiClient;
+use super::models::{
+    NetworkClient, NetworkProject, NetworkTag, NetworkTask, NetworkTimeEntry, NetworkWorkspace,
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+struct EntitiesCache {
+    /// The API the entries were fetched from, e.g. to not mix up a self-hosted
+    /// instance with track.toggl.com.
+    api_url: Option<String>,
+    workspaces: Option<CacheEntry<Vec<NetworkWorkspace>>>,
+    projects: Option<CacheEntry<Vec<NetworkProject>>>,
+    tasks: Option<CacheEntry<Vec<NetworkTask>>>,
+    clients: Option<CacheEntry<Vec<NetworkClient>>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct CacheEntry<T> {
+    fetched_at: DateTime<Utc>,
+    value: T,
+}
+
+impl<T> CacheEntry<T> {
+    fn new(value: T) -> Self {
+        Self {
+            fetched_at: Utc::now(),
+            value,
+        }
+    }
+}
+
+/// CachedApiClient keeps workspaces, projects, tasks and clients on disk so
+/// they don't have to be downloaded on every invocation. Entries older than the
+/// TTL are fetched again, as is everything when `refresh` is set. While
+/// offline, the cached entries are used regardless of their age. The cache is
+/// best effort, a missing or unreadable file behaves like an empty cache, and so
+/// does one that was filled from another API URL.
+pub struct CachedApiClient<C: ApiClient> {
+    api_client: C,
+    path: PathBuf,
+    ttl: Duration,
+    refresh: bool,
+    cache: Mutex<EntitiesCache>,
+}
+
+impl<C: ApiClient> CachedApiClient<C> {
+    pub fn new(
+        api_client: C,
+        api_url: String,
+        path: PathBuf,
+        ttl: Duration,
+        refresh: bool,
+    ) -> Self {
+        let cache = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<EntitiesCache>(&contents).ok())
+            .filter(|cache| cache.api_url.as_ref() == Some(&api_url))
+            .unwrap_or(EntitiesCache {
+                api_url: Some(api_url),
+                ..Default::default()
+            });
+        Self {
+            api_client,
+            path,
+            ttl,
+            refresh,
+            cache: Mutex::new(cache),
+        }
+    }
+
+    async fn fetch_cached<T: Clone>(
+        &self,
+        select: fn(&mut EntitiesCache) -> &mut Option<CacheEntry<T>>,
+        fetch: impl Future<Output = ResultWithDefaultError<T>>,
+    ) -> ResultWithDefaultError<T> {
+        if !self.refresh {
+            if let Some(value) = self.read(select, Some(self.ttl)) {
+                return Ok(value);
+            }
+        }
+        match fetch.await {
+            Ok(value) => {
+                self.write(select, value.clone());
+                Ok(value)
+            }
+            // Outdated entries are better than nothing while offline.
+            Err(error) if is_network_error(error.as_ref()) => self.read(select, None).ok_or(error),
+            Err(error) => Err(error),
+        }
+    }
+
+    fn read<T: Clone>(
+        &self,
+        select: fn(&mut EntitiesCache) -> &mut Option<CacheEntry<T>>,
+        ttl: Option<Duration>,
+    ) -> Option<T> {
+        let mut cache = self.cache.lock().unwrap();
+        select(&mut cache)
+            .as_ref()
+            .filter(|entry| ttl.map_or(true, |ttl| Utc::now() - entry.fetched_at < ttl))
+            .map(|entry| entry.value.clone())
+    }
+
+    fn write<T>(&self, select: fn(&mut EntitiesCache) -> &mut Option<CacheEntry<T>>, value: T) {
+        let mut cache = self.cache.lock().unwrap();
+        *select(&mut cache) = Some(CacheEntry::new(value));
+        self.save(&cache);
+    }
+
+    /// Drops cached entries that are known to be outdated, e.g. after creating one.
+    fn invalidate<T>(&self, select: fn(&mut EntitiesCache) -> &mut Option<CacheEntry<T>>) {
+        let mut cache = self.cache.lock().unwrap();
+        *select(&mut cache) = None;
+        self.save(&cache);
+    }
+
+    fn save(&self, cache: &EntitiesCache) {
+        if let Ok(contents) = serde_json::to_string(cache) {
+            if let Some(parent) = self.path.parent() {
+                let _ = std::fs::create_dir_all(parent);
+            }
+            let _ = std::fs::write(&self.path, contents);
+        }
+    }
+}
+
+#[async_trait]
+impl<C: ApiClient + Send> ApiClient for CachedApiClient<C> {
+    async fn get_user(&self) -> ResultWithDefaultError<User> {
+        self.api_client.get_user().await
+    }
+
+    async fn fetch_current_time_entry(&self) -> ResultWithDefaultError<Option<NetworkTimeEntry>> {
+        self.api_client.fetch_current_time_entry().await
+    }
+
+    async fn fetch_time_entry(&self, id: i64) -> ResultWithDefaultError<NetworkTimeEntry> {
+        self.api_client.fetch_time_entry(id).await
+    }
+
+    async fn fetch_time_entries(
+        &self,
+        range: &DateRange,
+    ) -> ResultWithDefaultError<Vec<NetworkTimeEntry>> {
+        self.api_client.fetch_time_entries(range).await
+    }
+
+    async fn fetch_workspaces(&self) -> ResultWithDefaultError<Vec<NetworkWorkspace>> {
+        self.fetch_cached(
+            |cache| &mut cache.workspaces,
+            self.api_client.fetch_workspaces(),
+        )
+        .await
+    }
+
+    async fn fetch_projects(&self) -> ResultWithDefaultError<Vec<NetworkProject>> {
+        self.fetch_cached(
+            |cache| &mut cache.projects,
+            self.api_client.fetch_projects(),
+        )
+        .await
+    }
+
+    async fn fetch_tasks(&self) -> ResultWithDefaultError<Vec<NetworkTask>> {
+        self.fetch_cached(|cache| &mut cache.tasks, self.api_client.fetch_tasks())
+            .await
+    }
+
+    async fn fetch_clients(&self) -> ResultWithDefaultError<Vec<NetworkClient>> {
+        self.fetch_cached(|cache| &mut cache.clients, self.api_client.fetch_clients())
+            .await
+    }
+
+    async fn fetch_tags(&self, workspace_id: i64) -> ResultWithDefaultError<Vec<NetworkTag>> {
+        self.api_client.fetch_tags(workspace_id).await
+    }
+
+    async fn create_time_entry(&self, time_entry: TimeEntry) -> ResultWithDefaultError<i64> {
+        self.api_client.create_time_entry(time_entry).await
+    }
+
+    async fn update_time_entry(&self, time_entry: TimeEntry) -> ResultWithDefaultError<i64> {
+        self.api_client.update_time_entry(time_entry).await
+    }
+
+    async fn delete_time_entry(&self, time_entry: TimeEntry) -> ResultWithDefaultError<()> {
+        self.api_client.delete_time_entry(time_entry).await
+    }
+
+    async fn create_project(
+        &self,
+        workspace_id: i64,
+        name: String,
+        client_id: Option<i64>,
+    ) -> ResultWithDefaultError<i64> {
+        let id = self
+            .api_client
+            .create_project(workspace_id, name, client_id)
+            .await?;
+        self.invalidate(|cache| &mut cache.projects);
+        Ok(id)
+    }
+
+    async fn update_project(&self, project: Project) -> ResultWithDefaultError<i64> {
+        let id = self.api_client.update_project(project).await?;
+        self.invalidate(|cache| &mut cache.projects);
+        Ok(id)
+    }
+
+    async fn create_task(&self, project: Project, name: String) -> ResultWithDefaultError<i64> {
+        let id = self.api_client.create_task(project, name).await?;
+        self.invalidate(|cache| &mut cache.tasks);
+        Ok(id)
+    }
+
+    async fn create_client(&self, workspace_id: i64, name: String) -> ResultWithDefaultError<i64> {
+        let id = self.api_client.create_client(workspace_id, name).await?;
+        self.invalidate(|cache| &mut cache.clients);
+        Ok(id)
+    }
+
+    async fn update_client(&self, client: Client) -> ResultWithDefaultError<i64> {
+        let id = self.api_client.update_client(client).await?;
+        self.invalidate(|cache| &mut cache.clients);
+        Ok(id)
+    }
+
+    async fn archive_client(&self, client: Client) -> ResultWithDefaultError<()> {
+        self.api_client.archive_client(client).await?;
+        self.invalidate(|cache| &mut cache.clients);
+        self.invalidate(|cache| &mut cache.projects);
+        Ok(())
+    }
+
+    async fn create_tag(&self, workspace_id: i64, name: String) -> ResultWithDefaultError<i64> {
+        self.api_client.create_tag(workspace_id, name).await
+    }
+
+    async fn update_tag(&self, tag: Tag) -> ResultWithDefaultError<i64> {
+        self.api_client.update_tag(tag).await
+    }
+
+    async fn delete_tag(&self, tag: Tag) -> ResultWithDefaultError<()> {
+        self.api_client.delete_tag(tag).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::client::MockApiClient;
+
+    const API_URL: &str = "https://track.toggl.com/api/v9";
+
+    fn create_cache_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "toggl-cli-cache-test-{}-{}.json",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    fn create_network_client() -> NetworkClient {
+        NetworkClient {
+            id: 1,
+            name: "Client".to_string(),
+            wid: 1,
+            archived: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn fresh_entries_are_read_from_disk() {
+        let path = create_cache_path("fresh");
+        let mut api_client = MockApiClient::new();
+        api_client
+            .expect_fetch_clients()
+            .times(1)
+            .returning(|| Ok(vec![create_network_client()]));
+        let _ = CachedApiClient::new(
+            api_client,
+            API_URL.to_string(),
+            path.clone(),
+            Duration::hours(1),
+            false,
+        )
+        .fetch_clients()
+        .await;
+
+        let api_client = CachedApiClient::new(
+            MockApiClient::new(),
+            API_URL.to_string(),
+            path,
+            Duration::hours(1),
+            false,
+        );
+        let clients = api_client.fetch_clients().await.unwrap();
+
+        assert_eq!(clients[0].name, "Client");
+    }
+
+    #[tokio::test]
+    async fn refreshing_ignores_the_cached_entries() {
+        let path = create_cache_path("refresh");
+        let mut api_client = MockApiClient::new();
+        api_client
+            .expect_fetch_clients()
+            .times(2)
+            .returning(|| Ok(vec![create_network_client()]));
+        let api_client = CachedApiClient::new(
+            api_client,
+            API_URL.to_string(),
+            path,
+            Duration::hours(1),
+            true,
+        );
+
+        let first = api_client.fetch_clients().await;
+        let second = api_client.fetch_clients().await;
+
+        assert!(first.is_ok() && second.is_ok());
+    }
+
+    #[tokio::test]
+    async fn entries_of_another_api_url_are_fetched_again() {
+        let path = create_cache_path("api-url");
+        let mut api_client = MockApiClient::new();
+        api_client
+            .expect_fetch_clients()
+            .times(1)
+            .returning(|| Ok(vec![create_network_client()]));
+        let _ = CachedApiClient::new(
+            api_client,
+            API_URL.to_string(),
+            path.clone(),
+            Duration::hours(1),
+            false,
+        )
+        .fetch_clients()
+        .await;
+
+        let mut api_client = MockApiClient::new();
+        api_client
+            .expect_fetch_clients()
+            .times(1)
+            .returning(|| Ok(vec![]));
+        let api_client = CachedApiClient::new(
+            api_client,
+            "http://localhost:8080/api/v9".to_string(),
+            path,
+            Duration::hours(1),
+            false,
+        );
+        let clients = api_client.fetch_clients().await.unwrap();
+
+        assert!(clients.is_empty());
+    }
+}
@@ -3,73 +3,207 @@ use std::collections::HashMap;
 use crate::credentials;
 use crate::error;
 use crate::models;
-use crate::models::Entities;
+use crate::models::Client;
+use crate::models::DateRange;
 use crate::models::Project;
+use crate::models::Tag;
 use crate::models::Task;
 use crate::models::TimeEntry;
+use crate::models::Workspace;
 use async_trait::async_trait;
 use base64::{engine::general_purpose, Engine as _};
-use error::ApiError;
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use error::{ApiError, ArgumentError};
 #[cfg(test)]
 use mockall::automock;
 use models::{ResultWithDefaultError, User};
-use reqwest::Client;
-use reqwest::{header, RequestBuilder};
+use reqwest::{header, RequestBuilder, Response, StatusCode};
 use serde::{de, Serialize};
+use serde_json::json;
+use std::time::Instant;
 
 use super::models::NetworkClient;
 use super::models::NetworkProject;
+use super::models::NetworkTag;
 use super::models::NetworkTask;
 use super::models::NetworkTimeEntry;
+use super::models::NetworkWorkspace;
+use super::retry::{self, RetryPolicy};
 
+/// ApiClient exposes the Toggl API in two layers. The `fetch_*` methods map
+/// one to one to API requests and return the raw network models, while the
+/// `get_*` methods resolve them into domain models, only fetching the related
+/// entities they need. Wrappers such as `MemoizedApiClient` only override the
+/// `fetch_*` methods so the resolution benefits from them for free.
 #[cfg_attr(test, automock)]
 #[async_trait]
-pub trait ApiClient {
+pub trait ApiClient: Sync {
     async fn get_user(&self) -> ResultWithDefaultError<User>;
-    async fn get_entities(&self) -> ResultWithDefaultError<Entities>;
+
+    async fn fetch_current_time_entry(&self) -> ResultWithDefaultError<Option<NetworkTimeEntry>>;
+    async fn fetch_time_entry(&self, id: i64) -> ResultWithDefaultError<NetworkTimeEntry>;
+    async fn fetch_time_entries(
+        &self,
+        range: &DateRange,
+    ) -> ResultWithDefaultError<Vec<NetworkTimeEntry>>;
+    async fn fetch_workspaces(&self) -> ResultWithDefaultError<Vec<NetworkWorkspace>>;
+    async fn fetch_projects(&self) -> ResultWithDefaultError<Vec<NetworkProject>>;
+    async fn fetch_tasks(&self) -> ResultWithDefaultError<Vec<NetworkTask>>;
+    async fn fetch_clients(&self) -> ResultWithDefaultError<Vec<NetworkClient>>;
+    async fn fetch_tags(&self, workspace_id: i64) -> ResultWithDefaultError<Vec<NetworkTag>>;
 
     async fn create_time_entry(&self, time_entry: TimeEntry) -> ResultWithDefaultError<i64>;
     async fn update_time_entry(&self, time_entry: TimeEntry) -> ResultWithDefaultError<i64>;
-}
+    async fn delete_time_entry(&self, time_entry: TimeEntry) -> ResultWithDefaultError<()>;
+    async fn create_project(
+        &self,
+        workspace_id: i64,
+        name: String,
+        client_id: Option<i64>,
+    ) -> ResultWithDefaultError<i64>;
+    async fn update_project(&self, project: Project) -> ResultWithDefaultError<i64>;
+    async fn create_task(&self, project: Project, name: String) -> ResultWithDefaultError<i64>;
+    async fn create_client(&self, workspace_id: i64, name: String) -> ResultWithDefaultError<i64>;
+    async fn update_client(&self, client: Client) -> ResultWithDefaultError<i64>;
+    async fn archive_client(&self, client: Client) -> ResultWithDefaultError<()>;
+    async fn create_tag(&self, workspace_id: i64, name: String) -> ResultWithDefaultError<i64>;
+    async fn update_tag(&self, tag: Tag) -> ResultWithDefaultError<i64>;
+    async fn delete_tag(&self, tag: Tag) -> ResultWithDefaultError<()>;
 
-pub struct V9ApiClient {
-    http_client: Client,
-    base_url: String,
-}
+    async fn get_workspaces(&self) -> ResultWithDefaultError<HashMap<i64, Workspace>> {
+        let workspaces = self.fetch_workspaces().await?;
+        Ok(workspaces
+            .iter()
+            .map(|w| (w.id, w.to_workspace()))
+            .collect())
+    }
 
-impl V9ApiClient {
-    async fn get_time_entries(&self) -> ResultWithDefaultError<Vec<NetworkTimeEntry>> {
-        let url = format!("{}/me/time_entries", self.base_url);
-        self.get::<Vec<NetworkTimeEntry>>(url).await
+    /// Resolves a workspace by its exact name, or the user's default workspace without one.
+    async fn get_workspace_id(&self, name: Option<String>) -> ResultWithDefaultError<i64> {
+        match name {
+            None => Ok(self.get_user().await?.default_workspace_id),
+            Some(name) => {
+                let workspaces = self.get_workspaces().await?;
+                workspaces
+                    .into_values()
+                    .find(|w| w.name == name)
+                    .map(|w| w.id)
+                    .ok_or_else(|| ArgumentError::WorkspaceNotFound(name).into())
+            }
+        }
     }
 
-    async fn get_projects(&self) -> ResultWithDefaultError<Vec<NetworkProject>> {
-        let url = format!("{}/me/projects", self.base_url);
-        self.get::<Vec<NetworkProject>>(url).await
+    async fn get_clients(&self) -> ResultWithDefaultError<HashMap<i64, Client>> {
+        let clients = self.fetch_clients().await?;
+        Ok(clients.iter().map(|c| (c.id, c.to_client())).collect())
     }
 
-    async fn get_clients(&self) -> ResultWithDefaultError<Vec<NetworkClient>> {
-        let url = format!("{}/me/clients", self.base_url);
-        self.get::<Vec<NetworkClient>>(url).await
+    async fn get_projects(&self) -> ResultWithDefaultError<HashMap<i64, Project>> {
+        let network_projects = self.fetch_projects().await?;
+        let clients = self.get_clients().await?;
+        Ok(network_projects
+            .iter()
+            .map(|p| (p.id, p.to_project(&clients)))
+            .collect())
     }
 
-    async fn get_tasks(&self) -> ResultWithDefaultError<Vec<NetworkTask>> {
-        let url = format!("{}/me/tasks", self.base_url);
-        self.get::<Vec<NetworkTask>>(url).await
+    async fn get_tasks(&self) -> ResultWithDefaultError<HashMap<i64, Task>> {
+        let network_tasks = self.fetch_tasks().await?;
+        let projects = self.get_projects().await?;
+        Ok(network_tasks
+            .iter()
+            .filter_map(|t| t.to_task(&projects).map(|task| (t.id, task)))
+            .collect())
+    }
+
+    async fn get_tags(&self, workspace_id: i64) -> ResultWithDefaultError<HashMap<i64, Tag>> {
+        let tags = self.fetch_tags(workspace_id).await?;
+        Ok(tags.iter().map(|t| (t.id, t.to_tag())).collect())
+    }
+
+    async fn get_running_time_entry(&self) -> ResultWithDefaultError<Option<TimeEntry>> {
+        let network_time_entry = self.fetch_current_time_entry().await?;
+        match network_time_entry {
+            None => Ok(None),
+            Some(network_time_entry) => {
+                Ok(Some(resolve_time_entry(self, network_time_entry).await?))
+            }
+        }
     }
 
+    async fn get_time_entry(&self, id: i64) -> ResultWithDefaultError<TimeEntry> {
+        let network_time_entry = self.fetch_time_entry(id).await?;
+        resolve_time_entry(self, network_time_entry).await
+    }
+
+    async fn get_time_entries(&self, range: DateRange) -> ResultWithDefaultError<Vec<TimeEntry>> {
+        let network_time_entries = self.fetch_time_entries(&range).await?;
+        let projects = self.get_projects().await?;
+        let tasks = self.get_tasks().await?;
+        Ok(network_time_entries
+            .iter()
+            .map(|te| te.to_time_entry(&projects, &tasks))
+            .collect())
+    }
+}
+
+/// Resolves a single time entry, only fetching projects and tasks if it refers to any.
+async fn resolve_time_entry<A: ApiClient + ?Sized>(
+    api_client: &A,
+    network_time_entry: NetworkTimeEntry,
+) -> ResultWithDefaultError<TimeEntry> {
+    let projects = match network_time_entry.project_id {
+        None => HashMap::new(),
+        Some(_) => api_client.get_projects().await?,
+    };
+    let tasks = match network_time_entry.task_id {
+        None => HashMap::new(),
+        Some(_) => api_client.get_tasks().await?,
+    };
+    Ok(network_time_entry.to_time_entry(&projects, &tasks))
+}
+
+pub struct V9ApiClient {
+    http_client: reqwest::Client,
+    base_url: String,
+    retry_policy: RetryPolicy,
+}
+
+impl V9ApiClient {
     pub fn from_credentials(
         credentials: credentials::Credentials,
         proxy: Option<String>,
+        base_url: String,
+    ) -> ResultWithDefaultError<V9ApiClient> {
+        Self::from_basic_auth(&credentials.api_token, "api_token", proxy, base_url)
+    }
+
+    /// A client authenticated with the email and password of the account,
+    /// which is only needed to fetch its API token.
+    pub fn from_email_and_password(
+        email: &str,
+        password: &str,
+        proxy: Option<String>,
+        base_url: String,
     ) -> ResultWithDefaultError<V9ApiClient> {
-        let auth_string = credentials.api_token + ":api_token";
+        Self::from_basic_auth(email, password, proxy, base_url)
+    }
+
+    fn from_basic_auth(
+        username: &str,
+        password: &str,
+        proxy: Option<String>,
+        base_url: String,
+    ) -> ResultWithDefaultError<V9ApiClient> {
+        let auth_string = format!("{}:{}", username, password);
         let header_content =
             "Basic ".to_string() + general_purpose::STANDARD.encode(auth_string).as_str();
         let mut headers = header::HeaderMap::new();
-        let auth_header = header::HeaderValue::from_str(header_content.as_str())?;
+        let mut auth_header = header::HeaderValue::from_str(header_content.as_str())?;
+        auth_header.set_sensitive(true);
         headers.insert(header::AUTHORIZATION, auth_header);
 
-        let base_client = Client::builder().default_headers(headers);
+        let base_client = reqwest::Client::builder().default_headers(headers);
         let http_client = {
             if let Some(proxy) = proxy {
                 base_client.proxy(reqwest::Proxy::all(proxy)?)
@@ -80,13 +214,21 @@ impl V9ApiClient {
         .build()?;
         let api_client = Self {
             http_client,
-            base_url: "https://track.toggl.com/api/v9".to_string(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            retry_policy: RetryPolicy::default(),
         };
         Ok(api_client)
     }
 
+    pub fn with_retry_policy(self, retry_policy: RetryPolicy) -> V9ApiClient {
+        Self {
+            retry_policy,
+            ..self
+        }
+    }
+
     async fn get<T: de::DeserializeOwned>(&self, url: String) -> ResultWithDefaultError<T> {
-        V9ApiClient::send::<T>(self.http_client.get(url)).await
+        self.send::<T>(self.http_client.get(url)).await
     }
 
     async fn put<T: de::DeserializeOwned, Body: Serialize>(
@@ -94,7 +236,7 @@ impl V9ApiClient {
         url: String,
         body: &Body,
     ) -> ResultWithDefaultError<T> {
-        V9ApiClient::send::<T>(self.http_client.put(url).json(body)).await
+        self.send::<T>(self.http_client.put(url).json(body)).await
     }
 
     async fn post<T: de::DeserializeOwned, Body: Serialize>(
@@ -102,20 +244,105 @@ impl V9ApiClient {
         url: String,
         body: &Body,
     ) -> ResultWithDefaultError<T> {
-        V9ApiClient::send::<T>(self.http_client.post(url).json(body)).await
+        self.send::<T>(self.http_client.post(url).json(body)).await
+    }
+
+    async fn delete(&self, url: String) -> ResultWithDefaultError<()> {
+        // Toggl answers deletions with an empty body, so only the status is checked.
+        let response = self.execute(self.http_client.delete(url)).await?;
+        V9ApiClient::check_status(response).await?;
+        Ok(())
+    }
+
+    async fn send<T: de::DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+    ) -> ResultWithDefaultError<T> {
+        let response = self.execute(request).await?;
+        V9ApiClient::parse_response::<T>(response).await
+    }
+
+    async fn execute(&self, request: RequestBuilder) -> ResultWithDefaultError<Response> {
+        let mut request = request.build()?;
+        let started_at = Instant::now();
+        let mut attempt = 1;
+        loop {
+            // Streamed bodies can't be cloned, so those requests are sent once.
+            let retry_request = request.try_clone();
+            let method = request.method().clone();
+            let response = match self.http_client.execute(request).await {
+                Err(_) => return Err(Box::new(ApiError::Network)),
+                Ok(response) => response,
+            };
+            if let Some(retry_request) = retry_request {
+                let delay = self.retry_policy.delay_before_retry(
+                    attempt,
+                    started_at.elapsed(),
+                    &method,
+                    response.status(),
+                    retry::get_retry_after(&response),
+                );
+                if let Some(delay) = delay {
+                    tokio::time::sleep(delay).await;
+                    request = retry_request;
+                    attempt += 1;
+                    continue;
+                }
+            }
+            return Ok(response);
+        }
     }
 
-    async fn send<T: de::DeserializeOwned>(request: RequestBuilder) -> ResultWithDefaultError<T> {
-        match request.send().await {
-            Err(_) => Err(Box::new(ApiError::Network)),
-            Ok(response) => match response.json::<T>().await {
-                Err(_) => Err(Box::new(ApiError::Deserialization)),
-                Ok(parsed_response) => Ok(parsed_response),
-            },
+    async fn check_status(response: Response) -> ResultWithDefaultError<Response> {
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(Box::new(api_error_from_response(status, body)));
+        }
+        Ok(response)
+    }
+
+    async fn parse_response<T: de::DeserializeOwned>(
+        response: Response,
+    ) -> ResultWithDefaultError<T> {
+        let response = V9ApiClient::check_status(response).await?;
+        match response.json::<T>().await {
+            Err(_) => Err(Box::new(ApiError::Deserialization)),
+            Ok(parsed_response) => Ok(parsed_response),
         }
     }
 }
 
+fn time_entries_query(range: &DateRange) -> Vec<(&'static str, String)> {
+    let format = |datetime: DateTime<Utc>| datetime.to_rfc3339_opts(SecondsFormat::Secs, true);
+    match (range.start, range.end) {
+        (Some(start), Some(end)) => vec![("start_date", format(start)), ("end_date", format(end))],
+        // Toggl only accepts start_date together with end_date, so leave room for running entries.
+        (Some(start), None) => vec![
+            ("start_date", format(start)),
+            ("end_date", format(Utc::now() + Duration::days(1))),
+        ],
+        (None, Some(end)) => vec![("before", format(end))],
+        (None, None) => vec![],
+    }
+}
+
+fn api_error_from_response(status: StatusCode, body: String) -> ApiError {
+    // Toggl usually sends errors as a JSON encoded string, but some endpoints answer in plain text.
+    let message = serde_json::from_str::<String>(&body)
+        .unwrap_or(body)
+        .trim()
+        .to_string();
+    match status {
+        StatusCode::UNAUTHORIZED => ApiError::Unauthorized(message),
+        StatusCode::FORBIDDEN => ApiError::Forbidden(message),
+        StatusCode::NOT_FOUND => ApiError::NotFound(message),
+        StatusCode::TOO_MANY_REQUESTS => ApiError::RateLimited(message),
+        status if status.is_server_error() => ApiError::Server(status.as_u16(), message),
+        status => ApiError::Request(status.as_u16(), message),
+    }
+}
+
 #[async_trait]
 impl ApiClient for V9ApiClient {
     async fn get_user(&self) -> ResultWithDefaultError<User> {
@@ -123,6 +350,50 @@ impl ApiClient for V9ApiClient {
         return self.get::<User>(url).await;
     }
 
+    async fn fetch_current_time_entry(&self) -> ResultWithDefaultError<Option<NetworkTimeEntry>> {
+        let url = format!("{}/me/time_entries/current", self.base_url);
+        self.get::<Option<NetworkTimeEntry>>(url).await
+    }
+
+    async fn fetch_time_entry(&self, id: i64) -> ResultWithDefaultError<NetworkTimeEntry> {
+        let url = format!("{}/me/time_entries/{}", self.base_url, id);
+        self.get::<NetworkTimeEntry>(url).await
+    }
+
+    async fn fetch_time_entries(
+        &self,
+        range: &DateRange,
+    ) -> ResultWithDefaultError<Vec<NetworkTimeEntry>> {
+        let url = format!("{}/me/time_entries", self.base_url);
+        let request = self.http_client.get(url).query(&time_entries_query(range));
+        self.send::<Vec<NetworkTimeEntry>>(request).await
+    }
+
+    async fn fetch_workspaces(&self) -> ResultWithDefaultError<Vec<NetworkWorkspace>> {
+        let url = format!("{}/me/workspaces", self.base_url);
+        self.get::<Vec<NetworkWorkspace>>(url).await
+    }
+
+    async fn fetch_projects(&self) -> ResultWithDefaultError<Vec<NetworkProject>> {
+        let url = format!("{}/me/projects", self.base_url);
+        self.get::<Vec<NetworkProject>>(url).await
+    }
+
+    async fn fetch_tasks(&self) -> ResultWithDefaultError<Vec<NetworkTask>> {
+        let url = format!("{}/me/tasks", self.base_url);
+        self.get::<Vec<NetworkTask>>(url).await
+    }
+
+    async fn fetch_clients(&self) -> ResultWithDefaultError<Vec<NetworkClient>> {
+        let url = format!("{}/me/clients", self.base_url);
+        self.get::<Vec<NetworkClient>>(url).await
+    }
+
+    async fn fetch_tags(&self, workspace_id: i64) -> ResultWithDefaultError<Vec<NetworkTag>> {
+        let url = format!("{}/workspaces/{}/tags", self.base_url, workspace_id);
+        self.get::<Vec<NetworkTag>>(url).await
+    }
+
     async fn create_time_entry(&self, time_entry: TimeEntry) -> ResultWithDefaultError<i64> {
         let url = format!("{}/time_entries", self.base_url);
         let network_time_entry = self
@@ -139,88 +410,309 @@ impl ApiClient for V9ApiClient {
         return Ok(network_time_entry.id);
     }
 
-    async fn get_entities(&self) -> ResultWithDefaultError<Entities> {
-        let network_time_entries = self.get_time_entries();
-        let network_projects = self.get_projects();
-        let network_tasks = self.get_tasks();
-        let network_clients = self.get_clients();
+    async fn delete_time_entry(&self, time_entry: TimeEntry) -> ResultWithDefaultError<()> {
+        let url = format!(
+            "{}/workspaces/{}/time_entries/{}",
+            self.base_url, time_entry.workspace_id, time_entry.id
+        );
+        self.delete(url).await
+    }
 
-        let clients: HashMap<i64, crate::models::Client> = network_clients
-            .await?
-            .iter()
-            .map(|c| {
-                (
-                    c.id,
-                    crate::models::Client {
-                        id: c.id,
-                        name: c.name.clone(),
-                        workspace_id: c.wid,
-                    },
-                )
-            })
-            .collect();
+    async fn create_project(
+        &self,
+        workspace_id: i64,
+        name: String,
+        client_id: Option<i64>,
+    ) -> ResultWithDefaultError<i64> {
+        let url = format!("{}/workspaces/{}/projects", self.base_url, workspace_id);
+        let body = json!({ "name": name, "client_id": client_id, "active": true });
+        let network_project = self.post::<NetworkProject, _>(url, &body).await?;
+        Ok(network_project.id)
+    }
 
-        let projects: HashMap<i64, Project> = network_projects
-            .await?
-            .iter()
-            .map(|p| {
-                (
-                    p.id,
-                    Project {
-                        id: p.id,
-                        name: p.name.clone(),
-                        workspace_id: p.workspace_id,
-                        client: clients.get(&p.client_id.unwrap_or(-1)).cloned(),
-                        is_private: p.is_private,
-                        active: p.active,
-                        at: p.at,
-                        created_at: p.created_at,
-                        color: p.color.clone(),
-                        billable: p.billable,
-                    },
-                )
-            })
-            .collect();
+    async fn update_project(&self, project: Project) -> ResultWithDefaultError<i64> {
+        let url = format!(
+            "{}/workspaces/{}/projects/{}",
+            self.base_url, project.workspace_id, project.id
+        );
+        let body = json!({
+            "name": project.name,
+            "client_id": project.client.map(|c| c.id),
+            "active": project.active,
+        });
+        let network_project = self.put::<NetworkProject, _>(url, &body).await?;
+        Ok(network_project.id)
+    }
 
-        let tasks: HashMap<i64, Task> = network_tasks
-            .await?
-            .iter()
-            .map(|t| {
-                (
-                    t.id,
-                    Task {
-                        id: t.id,
-                        name: t.name.clone(),
-                        project: projects.get(&t.project_id).unwrap().clone(),
-                        workspace_id: t.workspace_id,
-                    },
-                )
-            })
-            .collect();
+    async fn create_task(&self, project: Project, name: String) -> ResultWithDefaultError<i64> {
+        let url = format!(
+            "{}/workspaces/{}/projects/{}/tasks",
+            self.base_url, project.workspace_id, project.id
+        );
+        let body = json!({ "name": name, "active": true });
+        let network_task = self.post::<NetworkTask, _>(url, &body).await?;
+        Ok(network_task.id)
+    }
 
-        let time_entries = network_time_entries
-            .await?
-            .iter()
-            .map(|te| TimeEntry {
-                id: te.id,
-                description: te.description.clone(),
-                start: te.start,
-                stop: te.stop,
-                duration: te.duration,
-                billable: te.billable,
-                workspace_id: te.workspace_id,
-                tags: te.tags.clone(),
-                project: projects.get(&te.project_id.unwrap_or(-1)).cloned(),
-                task: tasks.get(&te.task_id.unwrap_or(-1)).cloned(),
-                ..Default::default()
+    async fn create_client(&self, workspace_id: i64, name: String) -> ResultWithDefaultError<i64> {
+        let url = format!("{}/workspaces/{}/clients", self.base_url, workspace_id);
+        let network_client = self
+            .post::<NetworkClient, _>(url, &json!({ "name": name }))
+            .await?;
+        Ok(network_client.id)
+    }
+
+    async fn update_client(&self, client: Client) -> ResultWithDefaultError<i64> {
+        let url = format!(
+            "{}/workspaces/{}/clients/{}",
+            self.base_url, client.workspace_id, client.id
+        );
+        let network_client = self
+            .put::<NetworkClient, _>(url, &json!({ "name": client.name }))
+            .await?;
+        Ok(network_client.id)
+    }
+
+    async fn archive_client(&self, client: Client) -> ResultWithDefaultError<()> {
+        let url = format!(
+            "{}/workspaces/{}/clients/{}/archive",
+            self.base_url, client.workspace_id, client.id
+        );
+        // Toggl answers with the IDs of the archived projects of the client.
+        let response = self.execute(self.http_client.post(url)).await?;
+        V9ApiClient::check_status(response).await?;
+        Ok(())
+    }
+
+    async fn create_tag(&self, workspace_id: i64, name: String) -> ResultWithDefaultError<i64> {
+        let url = format!("{}/workspaces/{}/tags", self.base_url, workspace_id);
+        let network_tag = self
+            .post::<NetworkTag, _>(url, &json!({ "name": name }))
+            .await?;
+        Ok(network_tag.id)
+    }
+
+    async fn update_tag(&self, tag: Tag) -> ResultWithDefaultError<i64> {
+        let url = format!(
+            "{}/workspaces/{}/tags/{}",
+            self.base_url, tag.workspace_id, tag.id
+        );
+        let network_tag = self
+            .put::<NetworkTag, _>(url, &json!({ "name": tag.name }))
+            .await?;
+        Ok(network_tag.id)
+    }
+
+    async fn delete_tag(&self, tag: Tag) -> ResultWithDefaultError<()> {
+        let url = format!(
+            "{}/workspaces/{}/tags/{}",
+            self.base_url, tag.workspace_id, tag.id
+        );
+        self.delete(url).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::auth::AuthenticationCommand;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const USER_BODY: &str = "{\"api_token\":\"token\",\"email\":\"toggl@user.org\",\"timezone\":\"UTC\",\"default_workspace_id\":1}";
+    const WORKSPACES_BODY: &str =
+        "[{\"id\":1,\"name\":\"Work\"},{\"id\":2,\"name\":\"Side project\"}]";
+    const RATE_LIMITED_RESPONSE: &str = "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 0\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+    const UNAVAILABLE_RESPONSE: &str =
+        "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+    /// Answers one connection per canned response, in order, and counts the requests it received.
+    async fn start_mock_server(responses: Vec<String>) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buffer = [0; 4096];
+                let _ = stream.read(&mut buffer).await;
+                counter.fetch_add(1, Ordering::SeqCst);
+                stream.write_all(response.as_bytes()).await.unwrap();
+                let _ = stream.shutdown().await;
+            }
+        });
+        (format!("http://{}", address), requests)
+    }
+
+    fn json_response(body: &str) -> String {
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+    }
+
+    fn user_response() -> String {
+        json_response(USER_BODY)
+    }
+
+    fn create_api_client(base_url: String, max_attempts: u32) -> V9ApiClient {
+        let credentials = credentials::Credentials {
+            api_token: "token".to_string(),
+        };
+        V9ApiClient::from_credentials(credentials, None, base_url)
+            .unwrap()
+            .with_retry_policy(RetryPolicy {
+                max_attempts,
+                initial_backoff: Duration::from_millis(1),
+                ..RetryPolicy::default()
             })
-            .collect();
-
-        Ok(Entities {
-            time_entries,
-            projects,
-            tasks,
-            clients,
-        })
+    }
+
+    #[test]
+    fn an_open_ended_range_before_a_date_uses_the_before_parameter() {
+        let end = DateTime::parse_from_rfc3339("2023-04-01T00:00:00+02:00").unwrap();
+        let range = DateRange::new(None, Some(end.with_timezone(&Utc)));
+
+        assert_eq!(
+            time_entries_query(&range),
+            vec![("before", "2023-03-31T22:00:00Z".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn logging_in_with_a_password_persists_the_api_token_of_the_user() {
+        let (base_url, _) = start_mock_server(vec![user_response()]).await;
+        let api_client =
+            V9ApiClient::from_email_and_password("toggl@user.org", "p w", None, base_url).unwrap();
+        let mut credentials_storage = credentials::MockCredentialsStorage::new();
+        credentials_storage
+            .expect_persist()
+            .withf(|api_token| api_token == "token")
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let result =
+            AuthenticationCommand::execute(Vec::new(), api_client, credentials_storage, true).await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn rate_limited_get_requests_are_retried() {
+        let (base_url, requests) =
+            start_mock_server(vec![RATE_LIMITED_RESPONSE.to_string(), user_response()]).await;
+        let api_client = create_api_client(base_url, 3);
+
+        let user = api_client.get_user().await;
+
+        assert_eq!(user.unwrap().email, "toggl@user.org");
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn server_errors_are_returned_once_attempts_are_exhausted() {
+        let (base_url, requests) = start_mock_server(vec![
+            UNAVAILABLE_RESPONSE.to_string(),
+            UNAVAILABLE_RESPONSE.to_string(),
+        ])
+        .await;
+        let api_client = create_api_client(base_url, 2);
+
+        let error = api_client.get_user().await.unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<ApiError>(),
+            Some(ApiError::Server(503, _))
+        ));
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn post_requests_are_not_retried() {
+        let (base_url, requests) =
+            start_mock_server(vec![UNAVAILABLE_RESPONSE.to_string(), user_response()]).await;
+        let api_client = create_api_client(base_url, 3);
+
+        let result = api_client.create_time_entry(TimeEntry::default()).await;
+
+        assert!(result.is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn rate_limited_post_requests_are_retried() {
+        let (base_url, requests) = start_mock_server(vec![
+            RATE_LIMITED_RESPONSE.to_string(),
+            json_response("{\"id\":7,\"name\":\"meeting\",\"workspace_id\":1}"),
+        ])
+        .await;
+        let api_client = create_api_client(base_url, 3);
+
+        let id = api_client.create_tag(1, "meeting".to_string()).await;
+
+        assert_eq!(id.unwrap(), 7);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn workspaces_are_resolved_by_name() {
+        let (base_url, _) = start_mock_server(vec![
+            json_response(WORKSPACES_BODY),
+            json_response(WORKSPACES_BODY),
+        ])
+        .await;
+        let api_client = create_api_client(base_url, 1);
+
+        let workspace_id = api_client
+            .get_workspace_id(Some("Side project".to_string()))
+            .await;
+        let error = api_client
+            .get_workspace_id(Some("Unknown".to_string()))
+            .await
+            .unwrap_err();
+
+        assert_eq!(workspace_id.unwrap(), 2);
+        assert!(matches!(
+            error.downcast_ref::<ArgumentError>(),
+            Some(ArgumentError::WorkspaceNotFound(name)) if name == "Unknown"
+        ));
+    }
+
+    #[test]
+    fn json_encoded_error_bodies_are_unwrapped() {
+        let error = api_error_from_response(
+            StatusCode::FORBIDDEN,
+            "\"Incorrect username and/or password\"\n".to_string(),
+        );
+
+        assert!(
+            matches!(error, ApiError::Forbidden(message) if message == "Incorrect username and/or password")
+        );
+    }
+
+    #[test]
+    fn plain_text_error_bodies_are_kept_as_is() {
+        let error = api_error_from_response(StatusCode::NOT_FOUND, "Not Found\n".to_string());
+
+        assert!(matches!(error, ApiError::NotFound(message) if message == "Not Found"));
+    }
+
+    #[test]
+    fn server_errors_carry_their_status_code() {
+        let error = api_error_from_response(StatusCode::BAD_GATEWAY, "".to_string());
+
+        assert!(matches!(error, ApiError::Server(502, message) if message.is_empty()));
+    }
+
+    #[test]
+    fn unexpected_client_errors_carry_their_status_code() {
+        let error = api_error_from_response(StatusCode::BAD_REQUEST, "\"invalid tag\"".to_string());
+
+        assert!(matches!(error, ApiError::Request(400, message) if message == "invalid tag"));
     }
 }
@@ -0,0 +1,219 @@
+use std::future::Future;
+
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+
+use crate::models::{Client, DateRange, Project, ResultWithDefaultError, Tag, TimeEntry, User};
+
+use super::client::ApiClient;
+use super::models::{
+    NetworkClient, NetworkProject, NetworkTag, NetworkTask, NetworkTimeEntry, NetworkWorkspace,
+};
+
+/// MemoizedApiClient remembers the user and the workspace metadata (workspaces,
+/// projects, tasks and clients) for the lifetime of a single invocation, so
+/// commands can ask for them as often as they like without repeating requests.
+/// Time entries change with every write and are always fetched from the
+/// wrapped client, while the memoized entities are forgotten once they change.
+pub struct MemoizedApiClient<C: ApiClient> {
+    api_client: C,
+    user: Mutex<Option<User>>,
+    workspaces: Mutex<Option<Vec<NetworkWorkspace>>>,
+    projects: Mutex<Option<Vec<NetworkProject>>>,
+    tasks: Mutex<Option<Vec<NetworkTask>>>,
+    clients: Mutex<Option<Vec<NetworkClient>>>,
+}
+
+impl<C: ApiClient> MemoizedApiClient<C> {
+    pub fn new(api_client: C) -> Self {
+        Self {
+            api_client,
+            user: Mutex::new(None),
+            workspaces: Mutex::new(None),
+            projects: Mutex::new(None),
+            tasks: Mutex::new(None),
+            clients: Mutex::new(None),
+        }
+    }
+}
+
+async fn memoize<T: Clone>(
+    memo: &Mutex<Option<T>>,
+    fetch: impl Future<Output = ResultWithDefaultError<T>>,
+) -> ResultWithDefaultError<T> {
+    // Holding the lock while fetching makes concurrent callers wait for the first request.
+    let mut memo = memo.lock().await;
+    if let Some(value) = memo.as_ref() {
+        return Ok(value.clone());
+    }
+    let value = fetch.await?;
+    *memo = Some(value.clone());
+    Ok(value)
+}
+
+#[async_trait]
+impl<C: ApiClient + Send> ApiClient for MemoizedApiClient<C> {
+    async fn get_user(&self) -> ResultWithDefaultError<User> {
+        memoize(&self.user, self.api_client.get_user()).await
+    }
+
+    async fn fetch_current_time_entry(&self) -> ResultWithDefaultError<Option<NetworkTimeEntry>> {
+        self.api_client.fetch_current_time_entry().await
+    }
+
+    async fn fetch_time_entry(&self, id: i64) -> ResultWithDefaultError<NetworkTimeEntry> {
+        self.api_client.fetch_time_entry(id).await
+    }
+
+    async fn fetch_time_entries(
+        &self,
+        range: &DateRange,
+    ) -> ResultWithDefaultError<Vec<NetworkTimeEntry>> {
+        self.api_client.fetch_time_entries(range).await
+    }
+
+    async fn fetch_workspaces(&self) -> ResultWithDefaultError<Vec<NetworkWorkspace>> {
+        memoize(&self.workspaces, self.api_client.fetch_workspaces()).await
+    }
+
+    async fn fetch_projects(&self) -> ResultWithDefaultError<Vec<NetworkProject>> {
+        memoize(&self.projects, self.api_client.fetch_projects()).await
+    }
+
+    async fn fetch_tasks(&self) -> ResultWithDefaultError<Vec<NetworkTask>> {
+        memoize(&self.tasks, self.api_client.fetch_tasks()).await
+    }
+
+    async fn fetch_clients(&self) -> ResultWithDefaultError<Vec<NetworkClient>> {
+        memoize(&self.clients, self.api_client.fetch_clients()).await
+    }
+
+    async fn fetch_tags(&self, workspace_id: i64) -> ResultWithDefaultError<Vec<NetworkTag>> {
+        self.api_client.fetch_tags(workspace_id).await
+    }
+
+    async fn create_time_entry(&self, time_entry: TimeEntry) -> ResultWithDefaultError<i64> {
+        self.api_client.create_time_entry(time_entry).await
+    }
+
+    async fn update_time_entry(&self, time_entry: TimeEntry) -> ResultWithDefaultError<i64> {
+        self.api_client.update_time_entry(time_entry).await
+    }
+
+    async fn delete_time_entry(&self, time_entry: TimeEntry) -> ResultWithDefaultError<()> {
+        self.api_client.delete_time_entry(time_entry).await
+    }
+
+    async fn create_project(
+        &self,
+        workspace_id: i64,
+        name: String,
+        client_id: Option<i64>,
+    ) -> ResultWithDefaultError<i64> {
+        let id = self
+            .api_client
+            .create_project(workspace_id, name, client_id)
+            .await?;
+        *self.projects.lock().await = None;
+        Ok(id)
+    }
+
+    async fn update_project(&self, project: Project) -> ResultWithDefaultError<i64> {
+        let id = self.api_client.update_project(project).await?;
+        *self.projects.lock().await = None;
+        Ok(id)
+    }
+
+    async fn create_task(&self, project: Project, name: String) -> ResultWithDefaultError<i64> {
+        let id = self.api_client.create_task(project, name).await?;
+        *self.tasks.lock().await = None;
+        Ok(id)
+    }
+
+    async fn create_client(&self, workspace_id: i64, name: String) -> ResultWithDefaultError<i64> {
+        let id = self.api_client.create_client(workspace_id, name).await?;
+        *self.clients.lock().await = None;
+        Ok(id)
+    }
+
+    async fn update_client(&self, client: Client) -> ResultWithDefaultError<i64> {
+        let id = self.api_client.update_client(client).await?;
+        *self.clients.lock().await = None;
+        Ok(id)
+    }
+
+    async fn archive_client(&self, client: Client) -> ResultWithDefaultError<()> {
+        self.api_client.archive_client(client).await?;
+        *self.clients.lock().await = None;
+        *self.projects.lock().await = None;
+        Ok(())
+    }
+
+    async fn create_tag(&self, workspace_id: i64, name: String) -> ResultWithDefaultError<i64> {
+        self.api_client.create_tag(workspace_id, name).await
+    }
+
+    async fn update_tag(&self, tag: Tag) -> ResultWithDefaultError<i64> {
+        self.api_client.update_tag(tag).await
+    }
+
+    async fn delete_tag(&self, tag: Tag) -> ResultWithDefaultError<()> {
+        self.api_client.delete_tag(tag).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::client::MockApiClient;
+    use chrono::Utc;
+
+    fn create_network_time_entry(project_id: Option<i64>) -> NetworkTimeEntry {
+        NetworkTimeEntry {
+            id: 1,
+            description: "Running".to_string(),
+            start: Utc::now(),
+            stop: None,
+            duration: -1,
+            billable: false,
+            workspace_id: 1,
+            tags: Vec::new(),
+            project_id,
+            task_id: None,
+            created_with: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn projects_and_clients_are_only_fetched_once() {
+        let mut api_client = MockApiClient::new();
+        api_client
+            .expect_fetch_projects()
+            .times(1)
+            .returning(|| Ok(Vec::new()));
+        api_client
+            .expect_fetch_clients()
+            .times(1)
+            .returning(|| Ok(Vec::new()));
+        let api_client = MemoizedApiClient::new(api_client);
+
+        let first = api_client.get_projects().await;
+        let second = api_client.get_projects().await;
+
+        assert!(first.is_ok() && second.is_ok());
+    }
+
+    #[tokio::test]
+    async fn a_running_entry_without_project_only_needs_a_single_request() {
+        let mut api_client = MockApiClient::new();
+        api_client
+            .expect_fetch_current_time_entry()
+            .times(1)
+            .returning(|| Ok(Some(create_network_time_entry(None))));
+        let api_client = MemoizedApiClient::new(api_client);
+
+        let running_time_entry = api_client.get_running_time_entry().await.unwrap();
+
+        assert_eq!(running_time_entry.unwrap().id, 1);
+    }
+}
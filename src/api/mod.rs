@@ -1,2 +1,5 @@
+pub mod cache;
 pub mod client;
+pub mod memoized;
 pub mod models;
+pub mod retry;
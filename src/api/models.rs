@@ -1,7 +1,9 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::models::TimeEntry;
+use crate::models::{Client, Project, Tag, Task, TimeEntry, Workspace};
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct NetworkTimeEntry {
@@ -38,6 +40,21 @@ pub struct NetworkClient {
     pub id: i64,
     pub name: String,
     pub wid: i64,
+    #[serde(default)]
+    pub archived: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NetworkWorkspace {
+    pub id: i64,
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NetworkTag {
+    pub id: i64,
+    pub name: String,
+    pub workspace_id: i64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -65,3 +82,84 @@ impl From<TimeEntry> for NetworkTimeEntry {
         }
     }
 }
+
+impl NetworkWorkspace {
+    pub fn to_workspace(&self) -> Workspace {
+        Workspace {
+            id: self.id,
+            name: self.name.clone(),
+        }
+    }
+}
+
+impl NetworkTag {
+    pub fn to_tag(&self) -> Tag {
+        Tag {
+            id: self.id,
+            name: self.name.clone(),
+            workspace_id: self.workspace_id,
+        }
+    }
+}
+
+impl NetworkClient {
+    pub fn to_client(&self) -> Client {
+        Client {
+            id: self.id,
+            name: self.name.clone(),
+            workspace_id: self.wid,
+            archived: self.archived,
+        }
+    }
+}
+
+impl NetworkProject {
+    pub fn to_project(&self, clients: &HashMap<i64, Client>) -> Project {
+        Project {
+            id: self.id,
+            name: self.name.clone(),
+            workspace_id: self.workspace_id,
+            client: clients.get(&self.client_id.unwrap_or(-1)).cloned(),
+            is_private: self.is_private,
+            active: self.active,
+            at: self.at,
+            created_at: self.created_at,
+            color: self.color.clone(),
+            billable: self.billable,
+        }
+    }
+}
+
+impl NetworkTask {
+    /// Tasks of projects that aren't available, e.g. archived ones, can't be resolved.
+    pub fn to_task(&self, projects: &HashMap<i64, Project>) -> Option<Task> {
+        projects.get(&self.project_id).map(|project| Task {
+            id: self.id,
+            name: self.name.clone(),
+            workspace_id: self.workspace_id,
+            project: project.clone(),
+        })
+    }
+}
+
+impl NetworkTimeEntry {
+    pub fn to_time_entry(
+        &self,
+        projects: &HashMap<i64, Project>,
+        tasks: &HashMap<i64, Task>,
+    ) -> TimeEntry {
+        TimeEntry {
+            id: self.id,
+            description: self.description.clone(),
+            start: self.start,
+            stop: self.stop,
+            duration: self.duration,
+            billable: self.billable,
+            workspace_id: self.workspace_id,
+            tags: self.tags.clone(),
+            project: projects.get(&self.project_id.unwrap_or(-1)).cloned(),
+            task: tasks.get(&self.task_id.unwrap_or(-1)).cloned(),
+            ..Default::default()
+        }
+    }
+}
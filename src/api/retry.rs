@@ -0,0 +1,189 @@
+use std::time::Duration;
+
+use reqwest::{header, Method, Response, StatusCode};
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// RetryPolicy decides whether a failed request is sent again and how long to
+/// wait before doing so. Rate limited requests were not processed by Toggl, so
+/// they're always retried, while server errors are only retried for idempotent
+/// requests. It backs off exponentially unless Toggl tells us how long to wait
+/// through the `Retry-After` header.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub timeout: Duration,
+    pub initial_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            timeout: DEFAULT_TIMEOUT,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: Option<u32>, timeout: Option<Duration>) -> Self {
+        let default = Self::default();
+        Self {
+            max_attempts: max_attempts.unwrap_or(default.max_attempts).max(1),
+            timeout: timeout.unwrap_or(default.timeout),
+            ..default
+        }
+    }
+
+    /// Returns how long to wait before sending attempt number `attempt + 1`,
+    /// or `None` if the response should be returned to the caller as is.
+    pub fn delay_before_retry(
+        &self,
+        attempt: u32,
+        elapsed: Duration,
+        method: &Method,
+        status: StatusCode,
+        retry_after: Option<Duration>,
+    ) -> Option<Duration> {
+        if !is_retryable(method, status) || attempt >= self.max_attempts {
+            return None;
+        }
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt - 1));
+        let delay = retry_after.unwrap_or(backoff);
+        if elapsed.saturating_add(delay) > self.timeout {
+            return None;
+        }
+        Some(delay)
+    }
+}
+
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS
+    )
+}
+
+pub fn get_retry_after(response: &Response) -> Option<Duration> {
+    // Toggl sends the delay in seconds, HTTP dates fall back to the regular backoff.
+    response
+        .headers()
+        .get(header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+fn is_retryable(method: &Method, status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || (status.is_server_error() && is_idempotent(method))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 4,
+            timeout: Duration::from_secs(10),
+            initial_backoff: Duration::from_secs(1),
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_with_every_attempt() {
+        let policy = policy();
+        let delays: Vec<Option<Duration>> = (1..=3)
+            .map(|attempt| {
+                policy.delay_before_retry(
+                    attempt,
+                    Duration::ZERO,
+                    &Method::GET,
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    None,
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            delays,
+            vec![
+                Some(Duration::from_secs(1)),
+                Some(Duration::from_secs(2)),
+                Some(Duration::from_secs(4))
+            ]
+        );
+    }
+
+    #[test]
+    fn retry_after_takes_precedence_over_backoff() {
+        let delay = policy().delay_before_retry(
+            1,
+            Duration::ZERO,
+            &Method::GET,
+            StatusCode::TOO_MANY_REQUESTS,
+            Some(Duration::from_secs(7)),
+        );
+
+        assert_eq!(delay, Some(Duration::from_secs(7)));
+    }
+
+    #[test]
+    fn client_errors_are_not_retried() {
+        let delay = policy().delay_before_retry(
+            1,
+            Duration::ZERO,
+            &Method::GET,
+            StatusCode::BAD_REQUEST,
+            None,
+        );
+
+        assert_eq!(delay, None);
+    }
+
+    #[test]
+    fn retries_stop_after_max_attempts() {
+        let delay = policy().delay_before_retry(
+            4,
+            Duration::ZERO,
+            &Method::GET,
+            StatusCode::BAD_GATEWAY,
+            None,
+        );
+
+        assert_eq!(delay, None);
+    }
+
+    #[test]
+    fn retries_stop_when_the_delay_exceeds_the_timeout() {
+        let delay = policy().delay_before_retry(
+            1,
+            Duration::from_secs(5),
+            &Method::GET,
+            StatusCode::TOO_MANY_REQUESTS,
+            Some(Duration::from_secs(6)),
+        );
+
+        assert_eq!(delay, None);
+    }
+
+    #[test]
+    fn non_idempotent_requests_are_only_retried_when_rate_limited() {
+        let delay =
+            |status| policy().delay_before_retry(1, Duration::ZERO, &Method::POST, status, None);
+
+        assert_eq!(
+            delay(StatusCode::TOO_MANY_REQUESTS),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(delay(StatusCode::SERVICE_UNAVAILABLE), None);
+    }
+}
@@ -1,7 +1,17 @@
 use std::path::PathBuf;
 
+use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
 use structopt::StructOpt;
 
+use crate::error::ArgumentError;
+use crate::models::DateRange;
+use crate::output::OutputFormat;
+use crate::profile;
+use crate::time_parser;
+use crate::timezone;
+use time_parser::{RangeExpression, TimeExpression};
+
 #[derive(Debug, StructOpt)]
 #[structopt(name = "toggl", about = "Toggl command line app.")]
 pub struct CommandLineArguments {
@@ -14,8 +24,51 @@ pub struct CommandLineArguments {
     #[structopt(long, help = "Use custom proxy")]
     pub proxy: Option<String>,
 
+    #[structopt(
+        long,
+        env = "TOGGL_API_URL",
+        help = "Base URL of the Toggl v9 API, overrides api_url from the global config"
+    )]
+    pub api_url: Option<String>,
+
+    #[structopt(
+        long,
+        parse(try_from_str = timezone::parse_timezone),
+        help = "Timezone to show and interpret times in, e.g. Europe/Berlin. Defaults to the one of your Toggl profile"
+    )]
+    pub tz: Option<Tz>,
+
+    #[structopt(
+        long,
+        default_value = "text",
+        possible_values = &OutputFormat::VARIANTS,
+        help = "Print time entries and configs as text, json, csv or tsv. Messages go to stderr unless it's text"
+    )]
+    pub output: OutputFormat,
+
+    #[structopt(
+        long,
+        global = true,
+        env = "TOGGL_PROFILE",
+        parse(try_from_str = profile::parse_profile),
+        help = "Name of the profile whose credentials to use, overrides the one pinned by the active config"
+    )]
+    pub profile: Option<String>,
+
+    #[structopt(
+        long,
+        help = "Name of the workspace to use, overrides the one of the active config and your default workspace"
+    )]
+    pub workspace: Option<String>,
+
     #[structopt(long, help = "Use fzf instead of the default picker")]
     pub fzf: bool,
+
+    #[structopt(
+        long,
+        help = "Ignore cached projects, tasks and clients and fetch them again"
+    )]
+    pub refresh: bool,
 }
 
 #[derive(Debug, StructOpt)]
@@ -25,35 +78,66 @@ pub enum Command {
     List {
         #[structopt(short, long)]
         number: Option<usize>,
+        #[structopt(long, help = "Only include entries of projects of this client")]
+        client: Option<String>,
+        #[structopt(flatten)]
+        range: DateRangeArguments,
     },
     Running,
-    Stop,
+    Stop {
+        #[structopt(flatten)]
+        time: TimeArguments,
+    },
     #[structopt(
         about = "Authenticate with the Toggl API. Find your API token at https://track.toggl.com/profile#api-token"
     )]
     Auth {
-        api_token: String,
+        #[structopt(required_unless = "email")]
+        api_token: Option<String>,
+        #[structopt(
+            long,
+            conflicts_with = "api-token",
+            help = "Log in with the email and password of your account instead, the password is prompted for"
+        )]
+        email: Option<String>,
     },
+    #[structopt(about = "Delete the stored API token of the profile")]
+    Logout,
+    #[structopt(about = "Show the account the API token belongs to")]
+    Whoami,
     #[structopt(
         about = "Start a new time entry, call with no arguments to start in interactive mode"
     )]
-    Start {
+    Start(StartArguments),
+    #[structopt(about = "Add a time entry that already ended, e.g. when you forgot to start one")]
+    Add(AddArguments),
+    Continue {
         #[structopt(short, long)]
         interactive: bool,
-        #[structopt(help = "Description of the time entry")]
-        description: Option<String>,
+        #[structopt(flatten)]
+        time: TimeArguments,
+    },
+    #[structopt(about = "Edit a time entry, call without an ID or flag to pick one interactively")]
+    Edit {
+        #[structopt(help = "ID of the time entry to edit")]
+        id: Option<i64>,
         #[structopt(
-            short,
             long,
-            help = "Exact name of the project you want the time entry to be associated with"
+            conflicts_with_all = &["id", "last"],
+            help = "Edit the running time entry"
         )]
-        project: Option<String>,
-        #[structopt(short, long)]
-        billable: bool,
+        current: bool,
+        #[structopt(long, conflicts_with = "id", help = "Edit the most recent time entry")]
+        last: bool,
+        #[structopt(flatten)]
+        changes: TimeEntryChanges,
     },
-    Continue {
-        #[structopt(short, long)]
-        interactive: bool,
+    #[structopt(about = "Delete time entries, call without IDs to pick them interactively")]
+    Delete {
+        #[structopt(help = "IDs of the time entries to delete")]
+        ids: Vec<i64>,
+        #[structopt(short, long, help = "Delete without asking for confirmation")]
+        yes: bool,
     },
     #[structopt(about = "Manage auto-tracking configuration")]
     Config {
@@ -73,6 +157,49 @@ pub enum Command {
         #[structopt(subcommand)]
         cmd: Option<ConfigSubCommand>,
     },
+    #[structopt(
+        about = "Sum up time entries by project, client, tag or description, defaults to this week"
+    )]
+    Report {
+        #[structopt(
+            long,
+            default_value = "project",
+            possible_values = &ReportGrouping::VARIANTS,
+            help = "What to group the time entries by"
+        )]
+        by: ReportGrouping,
+        #[structopt(long, help = "Only include entries of projects of this client")]
+        client: Option<String>,
+        #[structopt(flatten)]
+        range: DateRangeArguments,
+    },
+    #[structopt(about = "Replay start and stop operations recorded while offline")]
+    Sync,
+    #[structopt(about = "Manage the local cache of projects, tasks and clients")]
+    Cache {
+        #[structopt(subcommand)]
+        cmd: CacheSubCommand,
+    },
+    #[structopt(about = "Manage the clients of the workspace")]
+    Clients {
+        #[structopt(subcommand)]
+        cmd: ClientsSubCommand,
+    },
+    #[structopt(about = "Manage the projects of the workspace")]
+    Projects {
+        #[structopt(subcommand)]
+        cmd: ProjectsSubCommand,
+    },
+    #[structopt(about = "Manage the tasks of the projects in the workspace")]
+    Tasks {
+        #[structopt(subcommand)]
+        cmd: TasksSubCommand,
+    },
+    #[structopt(about = "Manage the tags of the workspace")]
+    Tags {
+        #[structopt(subcommand)]
+        cmd: TagsSubCommand,
+    },
 }
 
 #[derive(Debug, StructOpt)]
@@ -82,3 +209,332 @@ pub enum ConfigSubCommand {
     #[structopt(about = "Report matching configuration block for current directory.")]
     Active,
 }
+
+#[derive(Debug, StructOpt)]
+pub enum CacheSubCommand {
+    #[structopt(about = "Delete the cached projects, tasks and clients.")]
+    Clear,
+}
+
+#[derive(Debug, StructOpt)]
+pub enum ClientsSubCommand {
+    #[structopt(about = "List the active clients of the workspace.")]
+    List,
+    #[structopt(about = "Create a client.")]
+    Create { name: String },
+    #[structopt(about = "Rename a client.")]
+    Rename { name: String, new_name: String },
+    #[structopt(about = "Archive a client together with its projects.")]
+    Archive { name: String },
+}
+
+#[derive(Debug, StructOpt)]
+pub enum ProjectsSubCommand {
+    #[structopt(about = "List the active projects of the workspace.")]
+    List {
+        #[structopt(long, help = "Only list the projects of this client")]
+        client: Option<String>,
+    },
+    #[structopt(about = "Create a project.")]
+    Create {
+        name: String,
+        #[structopt(long, help = "Exact name of the client the project is for")]
+        client: Option<String>,
+    },
+    #[structopt(about = "Archive a project, its time entries are kept.")]
+    Archive { name: String },
+    #[structopt(about = "Rename a project.")]
+    Rename { name: String, new_name: String },
+}
+
+#[derive(Debug, StructOpt)]
+pub enum TasksSubCommand {
+    #[structopt(about = "List the tasks of the workspace.")]
+    List {
+        #[structopt(short, long, help = "Only list the tasks of this project")]
+        project: Option<String>,
+    },
+    #[structopt(about = "Create a task in a project.")]
+    Create {
+        name: String,
+        #[structopt(short, long, help = "Exact name of the project the task belongs to")]
+        project: String,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+pub enum TagsSubCommand {
+    #[structopt(about = "List the tags of the workspace.")]
+    List,
+    #[structopt(about = "Create a tag.")]
+    Create { name: String },
+    #[structopt(about = "Rename a tag, time entries keep it under its new name.")]
+    Rename { name: String, new_name: String },
+    #[structopt(about = "Delete a tag, it's removed from all time entries.")]
+    Delete {
+        name: String,
+        #[structopt(short, long, help = "Delete without asking for confirmation")]
+        yes: bool,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+pub struct StartArguments {
+    #[structopt(short, long)]
+    pub interactive: bool,
+    #[structopt(help = "Description of the time entry")]
+    pub description: Option<String>,
+    #[structopt(
+        short,
+        long,
+        help = "Exact name of the project you want the time entry to be associated with"
+    )]
+    pub project: Option<String>,
+    #[structopt(long, help = "Exact name of the task, within the project")]
+    pub task: Option<String>,
+    #[structopt(
+        short,
+        long = "tag",
+        number_of_values = 1,
+        help = "Tag the time entry, can be repeated"
+    )]
+    pub tags: Vec<String>,
+    #[structopt(short, long)]
+    pub billable: bool,
+    #[structopt(flatten)]
+    pub time: TimeArguments,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct AddArguments {
+    #[structopt(help = "Description of the time entry")]
+    pub description: Option<String>,
+    #[structopt(
+        short,
+        long,
+        help = "Exact name of the project you want the time entry to be associated with"
+    )]
+    pub project: Option<String>,
+    #[structopt(long, help = "Exact name of the task, within the project")]
+    pub task: Option<String>,
+    #[structopt(
+        short,
+        long = "tag",
+        number_of_values = 1,
+        help = "Tag the time entry, can be repeated"
+    )]
+    pub tags: Vec<String>,
+    #[structopt(short, long)]
+    pub billable: bool,
+    #[structopt(
+        long,
+        help = "When the time entry started, e.g. 09:00 or yesterday 14:00"
+    )]
+    pub start: TimeExpression,
+    #[structopt(
+        long,
+        required_unless = "duration",
+        help = "When the time entry stopped, e.g. 10:30 or yesterday 15:00"
+    )]
+    pub stop: Option<TimeExpression>,
+    #[structopt(
+        long,
+        parse(try_from_str = time_parser::parse_duration),
+        conflicts_with = "stop",
+        help = "How long the time entry lasted, e.g. 1h30m, 90m or 1.5h"
+    )]
+    pub duration: Option<Duration>,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct TimeEntryChanges {
+    #[structopt(short, long, help = "New description of the time entry")]
+    pub description: Option<String>,
+    #[structopt(short, long, help = "Exact name of the new project")]
+    pub project: Option<String>,
+    #[structopt(long, help = "Exact name of the new task, within the project")]
+    pub task: Option<String>,
+    #[structopt(
+        short,
+        long = "tag",
+        number_of_values = 1,
+        help = "Replace the tags, can be repeated"
+    )]
+    pub tags: Vec<String>,
+    #[structopt(short, long, parse(try_from_str), help = "Either true or false")]
+    pub billable: Option<bool>,
+    #[structopt(long, help = "New start time, e.g. 09:00 or yesterday 14:00")]
+    pub start: Option<TimeExpression>,
+    #[structopt(
+        long,
+        help = "New stop time, stops a running entry, e.g. 10:30 or yesterday 15:00"
+    )]
+    pub stop: Option<TimeExpression>,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct TimeArguments {
+    #[structopt(
+        long,
+        help = "Use this time instead of now, e.g. 09:15 or \"yesterday 17:00\""
+    )]
+    pub at: Option<TimeExpression>,
+    #[structopt(
+        long,
+        parse(try_from_str = time_parser::parse_duration),
+        conflicts_with = "at",
+        help = "Use this long ago instead of now, e.g. 15m or 1h30m"
+    )]
+    pub ago: Option<Duration>,
+}
+
+impl TimeArguments {
+    pub fn resolve(&self) -> Result<DateTime<Utc>, ArgumentError> {
+        match (&self.at, self.ago) {
+            (Some(at), _) => Ok(at.resolve()),
+            (None, Some(ago)) => Utc::now()
+                .checked_sub_signed(ago)
+                .ok_or(ArgumentError::TimeOutOfRange),
+            (None, None) => Ok(Utc::now()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportGrouping {
+    Project,
+    Client,
+    Tag,
+    Description,
+}
+
+impl ReportGrouping {
+    pub const VARIANTS: [&'static str; 4] = ["project", "client", "tag", "description"];
+}
+
+impl std::str::FromStr for ReportGrouping {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "project" => Ok(ReportGrouping::Project),
+            "client" => Ok(ReportGrouping::Client),
+            "tag" => Ok(ReportGrouping::Tag),
+            "description" => Ok(ReportGrouping::Description),
+            _ => Err(format!("Can't group by {}", value)),
+        }
+    }
+}
+
+#[derive(Debug, StructOpt)]
+pub struct DateRangeArguments {
+    #[structopt(
+        long,
+        help = "Only include entries started at or after this time, e.g. 2023-01-01, monday or yesterday 14:00"
+    )]
+    pub since: Option<TimeExpression>,
+    #[structopt(
+        long,
+        help = "Only include entries started before this time, e.g. 2023-02-01 or today"
+    )]
+    pub until: Option<TimeExpression>,
+    #[structopt(
+        long,
+        conflicts_with_all = &["since", "until"],
+        help = "Only include entries started in START..END, END is excluded, e.g. monday..tomorrow"
+    )]
+    pub range: Option<RangeExpression>,
+    #[structopt(
+        long,
+        conflicts_with_all = &["since", "until", "range", "yesterday", "week", "month"],
+        help = "Only include entries started today"
+    )]
+    pub today: bool,
+    #[structopt(
+        long,
+        conflicts_with_all = &["since", "until", "range", "week", "month"],
+        help = "Only include entries started yesterday"
+    )]
+    pub yesterday: bool,
+    #[structopt(
+        long,
+        conflicts_with_all = &["since", "until", "range", "month"],
+        help = "Only include entries started this week"
+    )]
+    pub week: bool,
+    #[structopt(
+        long,
+        conflicts_with_all = &["since", "until", "range"],
+        help = "Only include entries started this month"
+    )]
+    pub month: bool,
+}
+
+impl From<DateRangeArguments> for DateRange {
+    fn from(arguments: DateRangeArguments) -> Self {
+        if arguments.today {
+            DateRange::today()
+        } else if arguments.yesterday {
+            DateRange::yesterday()
+        } else if arguments.week {
+            DateRange::this_week()
+        } else if arguments.month {
+            DateRange::this_month()
+        } else if let Some(range) = arguments.range {
+            DateRange::new(Some(range.start.resolve()), Some(range.end.resolve()))
+        } else {
+            DateRange::new(
+                arguments.since.map(|since| since.resolve()),
+                arguments.until.map(|until| until.resolve()),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_arguments(today: bool, week: bool, range: Option<&str>) -> DateRangeArguments {
+        DateRangeArguments {
+            since: Some("2023-01-01".parse().unwrap()),
+            until: None,
+            range: range.map(|range| range.parse().unwrap()),
+            today,
+            yesterday: false,
+            week,
+            month: false,
+        }
+    }
+
+    #[test]
+    fn shorthand_flags_take_precedence_over_each_other_in_order() {
+        let range: DateRange = create_arguments(true, true, Some("monday..tomorrow")).into();
+
+        assert_eq!(range, DateRange::today());
+    }
+
+    #[test]
+    fn a_range_takes_precedence_over_since_and_until() {
+        let range: DateRange =
+            create_arguments(false, false, Some("2023-02-01..2023-03-01")).into();
+
+        assert_eq!(
+            range.start,
+            Some("2023-02-01".parse::<TimeExpression>().unwrap().resolve())
+        );
+    }
+
+    #[test]
+    fn since_and_until_are_used_without_a_flag_or_range() {
+        let range: DateRange = create_arguments(false, false, None).into();
+
+        assert_eq!(
+            range,
+            DateRange::new(
+                Some("2023-01-01".parse::<TimeExpression>().unwrap().resolve()),
+                None
+            )
+        );
+    }
+}
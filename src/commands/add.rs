@@ -0,0 +1,145 @@
+use crate::api;
+use crate::arguments::AddArguments;
+use crate::commands;
+use crate::config;
+use crate::error;
+use crate::models;
+use crate::output;
+use api::client::ApiClient;
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use commands::projects::create_missing_project;
+use commands::start::{resolve_task, resolve_time_entry, retain_workspace};
+use error::ArgumentError;
+use models::{ResultWithDefaultError, TimeEntry};
+
+pub struct AddCommand;
+
+fn resolve_start_and_stop(
+    arguments: &AddArguments,
+) -> Result<(DateTime<Utc>, DateTime<Utc>), ArgumentError> {
+    let start = arguments.start.resolve();
+    // Either --stop or --duration is required by the argument parser.
+    let stop = match (&arguments.stop, arguments.duration) {
+        (Some(stop), _) => stop.resolve(),
+        (None, Some(duration)) => start
+            .checked_add_signed(duration)
+            .ok_or(ArgumentError::TimeOutOfRange)?,
+        (None, None) => start,
+    };
+    if stop < start {
+        return Err(ArgumentError::StopBeforeStart);
+    }
+    Ok((start, stop))
+}
+
+impl AddCommand {
+    pub async fn execute(
+        api_client: impl ApiClient,
+        arguments: AddArguments,
+        workspace: Option<String>,
+    ) -> ResultWithDefaultError<()> {
+        let (start, stop) = resolve_start_and_stop(&arguments)?;
+
+        let config_path = config::locate::locate_config_path()?;
+        let track_config = config::parser::get_config_from_file(config_path)?;
+        let workspace = workspace.or(track_config.get_active_config()?.workspace.clone());
+        let workspace_id = api_client.get_workspace_id(workspace).await?;
+        let mut projects = api_client.get_projects().await?;
+        let mut tasks = api_client.get_tasks().await?;
+        retain_workspace(workspace_id, &mut projects, &mut tasks);
+        let config = track_config.get_active_config()?;
+        create_missing_project(&api_client, config, workspace_id, &mut projects).await?;
+
+        let default_time_entry =
+            track_config.get_default_entry(Some(workspace_id), &projects, &tasks)?;
+        let resolved_time_entry = resolve_time_entry(
+            default_time_entry,
+            &projects,
+            arguments.description,
+            arguments.project,
+            arguments.billable,
+        );
+
+        let task = resolve_task(&resolved_time_entry, &tasks, arguments.task)?;
+        let tags = if arguments.tags.is_empty() {
+            resolved_time_entry.tags.clone()
+        } else {
+            arguments.tags
+        };
+
+        let time_entry_to_create = TimeEntry {
+            start,
+            task,
+            tags,
+            ..resolved_time_entry
+        }
+        .as_stopped_time_entry(stop);
+
+        let added_entry_id = api_client
+            .create_time_entry(time_entry_to_create.clone())
+            .await?;
+        let added_entry = TimeEntry {
+            id: added_entry_id,
+            ..time_entry_to_create
+        };
+        output::print_message("Time entry added".green());
+        output::print_item(Some(&added_entry));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::client::MockApiClient;
+    use chrono::Duration;
+
+    fn create_arguments(
+        start: &str,
+        stop: Option<&str>,
+        duration: Option<Duration>,
+    ) -> AddArguments {
+        AddArguments {
+            description: None,
+            project: None,
+            task: None,
+            tags: vec![],
+            billable: false,
+            start: start.parse().unwrap(),
+            stop: stop.map(|stop| stop.parse().unwrap()),
+            duration,
+        }
+    }
+
+    #[tokio::test]
+    async fn entries_stopping_before_they_start_are_rejected() {
+        let arguments = create_arguments("2023-01-02 10:00", Some("2023-01-02 09:00"), None);
+
+        let result = AddCommand::execute(MockApiClient::new(), arguments, None).await;
+
+        assert!(matches!(
+            result.err().unwrap().downcast_ref::<ArgumentError>(),
+            Some(ArgumentError::StopBeforeStart)
+        ));
+    }
+
+    #[test]
+    fn the_duration_is_added_to_the_start() {
+        let arguments = create_arguments("2023-01-02 09:00", None, Some(Duration::minutes(90)));
+
+        let (start, stop) = resolve_start_and_stop(&arguments).unwrap();
+
+        assert_eq!(stop - start, Duration::minutes(90));
+    }
+
+    #[test]
+    fn the_stop_time_is_used_as_given() {
+        let arguments = create_arguments("2023-01-02 09:00", Some("2023-01-02 09:45"), None);
+
+        let (start, stop) = resolve_start_and_stop(&arguments).unwrap();
+
+        assert_eq!(stop - start, Duration::minutes(45));
+    }
+}
@@ -1,8 +1,10 @@
 use crate::api::client::ApiClient;
 use crate::credentials;
+use crate::error;
 use crate::models;
 use colored::Colorize;
 use credentials::CredentialsStorage;
+use error::ApiError;
 use models::ResultWithDefaultError;
 use std::io::Write;
 
@@ -15,8 +17,20 @@ impl AuthenticationCommand {
         mut writer: W,
         api_client: impl ApiClient,
         credentials_storage: impl CredentialsStorage,
+        with_password: bool,
     ) -> ResultWithDefaultError<()> {
-        let user = api_client.get_user().await?;
+        // Toggl answers a wrong password with 401 or 403, neither of which is
+        // about an API token or access to a resource here.
+        let user = api_client.get_user().await.map_err(|error| {
+            match error.downcast_ref::<ApiError>() {
+                Some(ApiError::Unauthorized(message) | ApiError::Forbidden(message))
+                    if with_password =>
+                {
+                    ApiError::LoginRejected(message.clone()).into()
+                }
+                _ => error,
+            }
+        })?;
         credentials_storage.persist(user.api_token)?;
         writeln!(
             writer,
@@ -33,9 +47,8 @@ impl AuthenticationCommand {
 mod tests {
     use super::*;
     use crate::api::client::MockApiClient;
-    use crate::error;
     use credentials::MockCredentialsStorage;
-    use error::{ApiError, StorageError};
+    use error::StorageError;
     use models::User;
     use tokio_test::assert_err;
     use tokio_test::assert_ok;
@@ -93,7 +106,8 @@ mod tests {
 
         // Act
         let result =
-            AuthenticationCommand::execute(&mut output, api_client, credentials_storage).await;
+            AuthenticationCommand::execute(&mut output, api_client, credentials_storage, false)
+                .await;
 
         // Assert
         assert_ok!(result);
@@ -107,7 +121,8 @@ mod tests {
         let credentials_storage = create_working_credentials_storage();
 
         // Act
-        let _ = AuthenticationCommand::execute(&mut output, api_client, credentials_storage).await;
+        let _ = AuthenticationCommand::execute(&mut output, api_client, credentials_storage, false)
+            .await;
 
         // Assert
         let expected_output = format!(
@@ -130,7 +145,8 @@ mod tests {
 
         // Act
         let result =
-            AuthenticationCommand::execute(&mut output, api_client, credentials_storage).await;
+            AuthenticationCommand::execute(&mut output, api_client, credentials_storage, false)
+                .await;
 
         // Assert
         assert_err!(result);
@@ -145,9 +161,29 @@ mod tests {
 
         // Act
         let result =
-            AuthenticationCommand::execute(&mut output, api_client, credentials_storage).await;
+            AuthenticationCommand::execute(&mut output, api_client, credentials_storage, false)
+                .await;
 
         // Assert
         assert_err!(result);
     }
+
+    #[tokio::test]
+    async fn a_rejected_password_is_not_reported_as_a_rejected_api_token() {
+        let mut output = Vec::new();
+        let mut api_client = MockApiClient::new();
+        api_client
+            .expect_get_user()
+            .returning(|| Err(Box::new(ApiError::Unauthorized("".to_string()))));
+        let credentials_storage = create_working_credentials_storage();
+
+        let result =
+            AuthenticationCommand::execute(&mut output, api_client, credentials_storage, true)
+                .await;
+
+        assert!(matches!(
+            result.err().unwrap().downcast_ref::<ApiError>(),
+            Some(ApiError::LoginRejected(_))
+        ));
+    }
 }
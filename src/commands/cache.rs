@@ -0,0 +1,15 @@
+use crate::models::ResultWithDefaultError;
+use colored::Colorize;
+use std::path::Path;
+
+pub struct CacheClearCommand;
+
+impl CacheClearCommand {
+    pub async fn execute(path: &Path) -> ResultWithDefaultError<()> {
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        println!("{}", "Cache cleared".green());
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+use crate::api;
+use crate::error;
+use crate::models;
+use crate::output;
+use api::client::ApiClient;
+use colored::Colorize;
+use error::ArgumentError;
+use models::{Client, ResultWithDefaultError};
+
+pub struct ClientsListCommand;
+pub struct ClientsCreateCommand;
+pub struct ClientsRenameCommand;
+pub struct ClientsArchiveCommand;
+
+/// Finds an active client of the workspace by its exact name.
+pub async fn find_client(
+    api_client: &impl ApiClient,
+    workspace_id: i64,
+    name: String,
+) -> ResultWithDefaultError<Client> {
+    let clients = api_client.get_clients().await?;
+    clients
+        .into_values()
+        .find(|c| c.name == name && c.workspace_id == workspace_id && !c.archived)
+        .ok_or_else(|| ArgumentError::ClientNotFound(name).into())
+}
+
+impl ClientsListCommand {
+    pub async fn execute(
+        api_client: impl ApiClient,
+        workspace: Option<String>,
+    ) -> ResultWithDefaultError<()> {
+        let workspace_id = api_client.get_workspace_id(workspace).await?;
+        let mut clients: Vec<Client> = api_client
+            .get_clients()
+            .await?
+            .into_values()
+            .filter(|c| c.workspace_id == workspace_id && !c.archived)
+            .collect();
+        if clients.is_empty() {
+            output::print_message("No clients found".yellow());
+        }
+        clients.sort_by_key(|c| c.name.to_lowercase());
+        output::print_items(clients.iter());
+
+        Ok(())
+    }
+}
+
+impl ClientsCreateCommand {
+    pub async fn execute(
+        api_client: impl ApiClient,
+        workspace: Option<String>,
+        name: String,
+    ) -> ResultWithDefaultError<()> {
+        let workspace_id = api_client.get_workspace_id(workspace).await?;
+        let id = api_client.create_client(workspace_id, name.clone()).await?;
+        output::print_message("Client created".green());
+        output::print_item(Some(&Client {
+            id,
+            name,
+            workspace_id,
+            archived: false,
+        }));
+
+        Ok(())
+    }
+}
+
+impl ClientsRenameCommand {
+    pub async fn execute(
+        api_client: impl ApiClient,
+        workspace: Option<String>,
+        name: String,
+        new_name: String,
+    ) -> ResultWithDefaultError<()> {
+        let workspace_id = api_client.get_workspace_id(workspace).await?;
+        let client = find_client(&api_client, workspace_id, name).await?;
+        let renamed_client = Client {
+            name: new_name,
+            ..client
+        };
+        api_client.update_client(renamed_client.clone()).await?;
+        output::print_message("Client renamed".green());
+        output::print_item(Some(&renamed_client));
+
+        Ok(())
+    }
+}
+
+impl ClientsArchiveCommand {
+    pub async fn execute(
+        api_client: impl ApiClient,
+        workspace: Option<String>,
+        name: String,
+    ) -> ResultWithDefaultError<()> {
+        let workspace_id = api_client.get_workspace_id(workspace).await?;
+        let client = find_client(&api_client, workspace_id, name).await?;
+        api_client.archive_client(client.clone()).await?;
+        output::print_message("Client and its projects archived".green());
+        output::print_item(Some(&Client {
+            archived: true,
+            ..client
+        }));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::client::MockApiClient;
+    use std::collections::HashMap;
+
+    fn create_client(id: i64, workspace_id: i64, archived: bool) -> Client {
+        Client {
+            id,
+            name: "Acme".to_string(),
+            workspace_id,
+            archived,
+        }
+    }
+
+    #[tokio::test]
+    async fn only_active_clients_of_the_workspace_are_found() {
+        let mut api_client = MockApiClient::new();
+        api_client.expect_get_clients().returning(|| {
+            Ok(HashMap::from([
+                (1, create_client(1, 1, true)),
+                (2, create_client(2, 2, false)),
+                (3, create_client(3, 1, false)),
+            ]))
+        });
+
+        let client = find_client(&api_client, 1, "Acme".to_string()).await;
+
+        assert_eq!(client.unwrap().id, 3);
+    }
+}
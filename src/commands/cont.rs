@@ -1,11 +1,12 @@
 use crate::api::client::ApiClient;
 use crate::commands;
 use crate::models;
+use crate::output;
 use crate::picker;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use colored::Colorize;
 use commands::stop::{StopCommand, StopCommandOrigin};
-use models::{ResultWithDefaultError, TimeEntry};
+use models::{DateRange, ResultWithDefaultError, TimeEntry};
 use picker::{ItemPicker, PickableItem};
 
 pub struct ContinueCommand;
@@ -14,27 +15,27 @@ impl ContinueCommand {
     pub async fn execute(
         api_client: impl ApiClient,
         picker: Option<Box<dyn ItemPicker>>,
+        start_time: DateTime<Utc>,
     ) -> ResultWithDefaultError<()> {
         let running_time_entry =
-            StopCommand::execute(&api_client, StopCommandOrigin::ContinueCommand).await?;
+            StopCommand::execute(&api_client, StopCommandOrigin::ContinueCommand, start_time)
+                .await?;
 
-        let entities = api_client.get_entities().await?;
-        if entities.time_entries.is_empty() {
-            println!("{}", "No time entries in last 90 days".red());
+        let time_entries = api_client.get_time_entries(DateRange::default()).await?;
+        if time_entries.is_empty() {
+            output::print_message("No time entries in last 90 days".red());
             return Ok(());
         }
 
         let time_entry_to_continue = match picker {
-            None => get_first_stopped_time_entry(entities.time_entries, running_time_entry),
+            None => get_first_stopped_time_entry(time_entries, running_time_entry),
             Some(time_entry_picker) => {
-                let pickable_items = entities
-                    .time_entries
+                let pickable_items = time_entries
                     .iter()
                     .map(|te| PickableItem::from_time_entry(te.clone()))
                     .collect();
                 let picked_key = time_entry_picker.pick(pickable_items)?;
-                let picked_time_entry = entities
-                    .time_entries
+                let picked_time_entry = time_entries
                     .iter()
                     .find(|te| te.id == picked_key.id)
                     .unwrap();
@@ -43,22 +44,18 @@ impl ContinueCommand {
         };
 
         match time_entry_to_continue {
-            None => println!("{}", "No time entry to continue".red()),
+            None => output::print_message("No time entry to continue".red()),
             Some(time_entry) => {
-                let start_time = Utc::now();
                 let time_entry_to_create = time_entry.as_running_time_entry(start_time);
-                let continued_entry_id = api_client.create_time_entry(time_entry_to_create).await?;
-                let entities = api_client.get_entities().await?;
-                let continued_entry = entities
-                    .time_entries
-                    .iter()
-                    .find(|te| te.id == continued_entry_id)
-                    .unwrap();
-                println!(
-                    "{}\n{}",
-                    "Time entry continued successfully".green(),
-                    continued_entry
-                )
+                let continued_entry_id = api_client
+                    .create_time_entry(time_entry_to_create.clone())
+                    .await?;
+                let continued_entry = TimeEntry {
+                    id: continued_entry_id,
+                    ..time_entry_to_create
+                };
+                output::print_message("Time entry continued successfully".green());
+                output::print_item(Some(&continued_entry));
             }
         }
 
@@ -0,0 +1,153 @@
+use crate::api;
+use crate::models;
+use crate::picker;
+use crate::utilities;
+use api::client::ApiClient;
+use colored::Colorize;
+use models::{DateRange, ResultWithDefaultError, TimeEntry};
+use picker::{ItemPicker, PickableItem};
+
+pub struct DeleteCommand;
+
+impl DeleteCommand {
+    pub async fn execute(
+        api_client: impl ApiClient,
+        picker: Box<dyn ItemPicker>,
+        ids: Vec<i64>,
+        skip_confirmation: bool,
+    ) -> ResultWithDefaultError<()> {
+        let time_entries = if ids.is_empty() {
+            pick_time_entries(&api_client, picker).await?
+        } else {
+            let mut time_entries = Vec::new();
+            for id in ids {
+                time_entries.push(api_client.get_time_entry(id).await?);
+            }
+            time_entries
+        };
+
+        if time_entries.is_empty() {
+            println!("{}", "No time entries to delete".yellow());
+            return Ok(());
+        }
+
+        for time_entry in &time_entries {
+            println!("{}", time_entry);
+        }
+        let yes_or_default_no = [
+            "y".to_string(),
+            "n".to_string(),
+            "N".to_string(),
+            "".to_string(),
+        ];
+        if !skip_confirmation
+            && utilities::read_from_stdin_with_constraints(
+                &format!("Delete {}? (y/N): ", count_time_entries(time_entries.len())),
+                &yes_or_default_no,
+            ) != "y"
+        {
+            println!("{}", "No time entries were deleted".yellow());
+            return Ok(());
+        }
+
+        let count = time_entries.len();
+        for time_entry in time_entries {
+            api_client.delete_time_entry(time_entry).await?;
+        }
+        println!(
+            "{}",
+            format!("Deleted {}", count_time_entries(count)).green()
+        );
+
+        Ok(())
+    }
+}
+
+fn count_time_entries(count: usize) -> String {
+    match count {
+        1 => "1 time entry".to_string(),
+        count => format!("{} time entries", count),
+    }
+}
+
+async fn pick_time_entries(
+    api_client: &impl ApiClient,
+    picker: Box<dyn ItemPicker>,
+) -> ResultWithDefaultError<Vec<TimeEntry>> {
+    let time_entries = api_client.get_time_entries(DateRange::default()).await?;
+    if time_entries.is_empty() {
+        return Ok(Vec::new());
+    }
+    let pickable_items = time_entries
+        .iter()
+        .map(|te| PickableItem::from_time_entry(te.clone()))
+        .collect();
+    let picked_keys = picker.pick_many(pickable_items)?;
+    Ok(time_entries
+        .into_iter()
+        .filter(|te| picked_keys.iter().any(|key| key.id == te.id))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::client::MockApiClient;
+    use crate::picker::{PickableItemKey, PickableItemKind};
+
+    /// Picks the time entries with the given ids, whatever it's offered.
+    struct FixedPicker(Vec<i64>);
+
+    impl FixedPicker {
+        fn key(id: i64) -> PickableItemKey {
+            PickableItemKey {
+                id,
+                kind: PickableItemKind::TimeEntry,
+            }
+        }
+    }
+
+    impl ItemPicker for FixedPicker {
+        fn pick(&self, _: Vec<PickableItem>) -> ResultWithDefaultError<PickableItemKey> {
+            Ok(Self::key(self.0[0]))
+        }
+
+        fn pick_many(&self, _: Vec<PickableItem>) -> ResultWithDefaultError<Vec<PickableItemKey>> {
+            Ok(self.0.iter().map(|id| Self::key(*id)).collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn picked_time_entries_are_deleted_without_confirmation() {
+        let mut api_client = MockApiClient::new();
+        api_client.expect_get_time_entries().returning(|_| {
+            Ok((1..=3)
+                .map(|id| TimeEntry {
+                    id,
+                    ..TimeEntry::default()
+                })
+                .collect())
+        });
+        api_client
+            .expect_delete_time_entry()
+            .withf(|te| te.id == 1 || te.id == 2)
+            .times(2)
+            .returning(|_| Ok(()));
+
+        let result = DeleteCommand::execute(
+            api_client,
+            Box::new(FixedPicker(vec![1, 2])),
+            Vec::new(),
+            true,
+        )
+        .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn a_single_time_entry_is_counted_in_singular() {
+        assert_eq!(count_time_entries(1), "1 time entry");
+        assert_eq!(count_time_entries(2), "2 time entries");
+    }
+}
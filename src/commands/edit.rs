@@ -0,0 +1,191 @@
+use crate::api;
+use crate::arguments::TimeEntryChanges;
+use crate::error;
+use crate::models;
+use crate::output;
+use crate::picker;
+use api::client::ApiClient;
+use colored::Colorize;
+use error::ArgumentError;
+use models::{DateRange, ResultWithDefaultError, TimeEntry};
+use picker::{ItemPicker, PickableItem};
+
+pub struct EditCommand;
+
+pub enum EditTarget {
+    Id(i64),
+    Current,
+    Last,
+    Picked(Box<dyn ItemPicker>),
+}
+
+impl EditCommand {
+    pub async fn execute(
+        api_client: impl ApiClient,
+        target: EditTarget,
+        changes: TimeEntryChanges,
+    ) -> ResultWithDefaultError<()> {
+        let time_entry = match target {
+            EditTarget::Id(id) => Some(api_client.get_time_entry(id).await?),
+            EditTarget::Current => api_client.get_running_time_entry().await?,
+            EditTarget::Last => api_client
+                .get_time_entries(DateRange::default())
+                .await?
+                .first()
+                .cloned(),
+            EditTarget::Picked(picker) => {
+                let time_entries = api_client.get_time_entries(DateRange::default()).await?;
+                let pickable_items = time_entries
+                    .iter()
+                    .map(|te| PickableItem::from_time_entry(te.clone()))
+                    .collect();
+                let picked_key = picker.pick(pickable_items)?;
+                time_entries.into_iter().find(|te| te.id == picked_key.id)
+            }
+        };
+
+        let time_entry = match time_entry {
+            None => {
+                output::print_message("No time entry to edit".yellow());
+                return Ok(());
+            }
+            Some(time_entry) => time_entry,
+        };
+
+        let edited_time_entry = apply_changes(&api_client, time_entry, changes).await?;
+        api_client
+            .update_time_entry(edited_time_entry.clone())
+            .await?;
+        output::print_message("Time entry updated".green());
+        output::print_item(Some(&edited_time_entry));
+
+        Ok(())
+    }
+}
+
+async fn apply_changes(
+    api_client: &impl ApiClient,
+    time_entry: TimeEntry,
+    changes: TimeEntryChanges,
+) -> ResultWithDefaultError<TimeEntry> {
+    let (project, task) = match changes.project {
+        None => (time_entry.project.clone(), time_entry.task.clone()),
+        Some(name) => {
+            let project = api_client
+                .get_projects()
+                .await?
+                .into_values()
+                .find(|p| p.name == name && p.workspace_id == time_entry.workspace_id)
+                .ok_or(ArgumentError::ProjectNotFound(name))?;
+            // The previous task belongs to the previous project.
+            (Some(project), None)
+        }
+    };
+
+    let task = match changes.task {
+        None => task,
+        Some(name) => {
+            let project_id = project.as_ref().map(|p| p.id);
+            let task = api_client
+                .get_tasks()
+                .await?
+                .into_values()
+                .find(|t| t.name == name && Some(t.project.id) == project_id)
+                .ok_or(ArgumentError::TaskNotFound(name))?;
+            Some(task)
+        }
+    };
+
+    let start = changes
+        .start
+        .map(|start| start.resolve())
+        .unwrap_or(time_entry.start);
+    let stop = changes.stop.map(|stop| stop.resolve()).or(time_entry.stop);
+    if stop.map_or(false, |stop| stop < start) {
+        return Err(Box::new(ArgumentError::StopBeforeStart));
+    }
+
+    let edited_time_entry = TimeEntry {
+        description: changes
+            .description
+            .unwrap_or(time_entry.description.clone()),
+        billable: changes.billable.unwrap_or(time_entry.billable),
+        tags: if changes.tags.is_empty() {
+            time_entry.tags.clone()
+        } else {
+            changes.tags
+        },
+        project,
+        task,
+        start,
+        ..time_entry
+    };
+
+    Ok(match stop {
+        None => TimeEntry {
+            duration: -start.timestamp(),
+            ..edited_time_entry
+        },
+        Some(stop) => edited_time_entry.as_stopped_time_entry(stop),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::client::MockApiClient;
+    use chrono::{Duration, Utc};
+
+    fn no_changes() -> TimeEntryChanges {
+        TimeEntryChanges {
+            description: None,
+            project: None,
+            task: None,
+            tags: Vec::new(),
+            billable: None,
+            start: None,
+            stop: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn stopping_a_running_entry_sets_its_duration() {
+        let start = Utc::now() - Duration::hours(1);
+        let stop = start + Duration::minutes(30);
+        let mut api_client = MockApiClient::new();
+        api_client
+            .expect_get_running_time_entry()
+            .returning(move || Ok(Some(TimeEntry::default().as_running_time_entry(start))));
+        api_client
+            .expect_update_time_entry()
+            .withf(move |te| te.stop == Some(stop) && te.duration == 30 * 60)
+            .times(1)
+            .returning(|te| Ok(te.id));
+
+        let changes = TimeEntryChanges {
+            stop: Some(stop.to_rfc3339().parse().unwrap()),
+            ..no_changes()
+        };
+        let result = EditCommand::execute(api_client, EditTarget::Current, changes).await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn stop_before_start_is_rejected() {
+        let start = Utc::now() - Duration::hours(1);
+        let mut api_client = MockApiClient::new();
+        api_client
+            .expect_get_running_time_entry()
+            .returning(move || Ok(Some(TimeEntry::default().as_running_time_entry(start))));
+        api_client.expect_update_time_entry().never();
+
+        let changes = TimeEntryChanges {
+            stop: Some((start - Duration::minutes(1)).to_rfc3339().parse().unwrap()),
+            ..no_changes()
+        };
+        let result = EditCommand::execute(api_client, EditTarget::Current, changes).await;
+
+        assert!(result.is_err());
+    }
+}
@@ -1,29 +1,166 @@
 use crate::api;
+use crate::error::ArgumentError;
 use crate::models;
+use crate::output;
 use api::client::ApiClient;
 use colored::Colorize;
-use models::ResultWithDefaultError;
+use models::{DateRange, ResultWithDefaultError, TimeEntry};
 
 pub struct ListCommand;
 
+/// Time entries of all workspaces, or only of the given one, optionally only
+/// those of the projects of a client.
+pub async fn get_filtered_time_entries(
+    api_client: &impl ApiClient,
+    range: DateRange,
+    workspace: Option<String>,
+    client: Option<String>,
+) -> ResultWithDefaultError<Vec<TimeEntry>> {
+    let mut time_entries = api_client.get_time_entries(range).await?;
+    if workspace.is_some() {
+        let workspace_id = api_client.get_workspace_id(workspace).await?;
+        time_entries.retain(|te| te.workspace_id == workspace_id);
+    }
+    if let Some(name) = client {
+        let clients = api_client.get_clients().await?;
+        if !clients.values().any(|c| c.name == name) {
+            return Err(Box::new(ArgumentError::ClientNotFound(name)));
+        }
+        time_entries.retain(|te| {
+            te.project
+                .as_ref()
+                .and_then(|p| p.client.as_ref())
+                .map_or(false, |c| c.name == name)
+        });
+    }
+    Ok(time_entries)
+}
+
 impl ListCommand {
     pub async fn execute(
         api_client: impl ApiClient,
         count: Option<usize>,
+        range: DateRange,
+        workspace: Option<String>,
+        client: Option<String>,
     ) -> ResultWithDefaultError<()> {
-        match api_client.get_entities().await {
-            Err(error) => println!(
-                "{}\n{}",
-                "Couldn't fetch time entries the from API".red(),
-                error
-            ),
-            Ok(entities) => entities
-                .time_entries
-                .iter()
-                .take(count.unwrap_or(usize::max_value()))
-                .for_each(|time_entry| println!("{}", time_entry)),
+        let time_entries = get_filtered_time_entries(&api_client, range, workspace, client).await?;
+        if time_entries.is_empty() {
+            output::print_message("No time entries found".yellow());
         }
+        output::print_items(
+            time_entries
+                .iter()
+                .take(count.unwrap_or(usize::max_value())),
+        );
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::client::MockApiClient;
+    use crate::error::{self, ApiError};
+    use crate::models::{Client, Project};
+
+    fn create_client(id: i64, name: &str) -> Client {
+        Client {
+            id,
+            name: name.to_string(),
+            workspace_id: 1,
+            archived: false,
+        }
+    }
+
+    fn create_api_client() -> MockApiClient {
+        let acme = create_client(3, "Acme");
+        let mut api_client = MockApiClient::new();
+        api_client.expect_get_time_entries().returning(move |_| {
+            let project = |id, client: Option<Client>| Project {
+                client,
+                ..Project::fixture(id, "toggl-cli")
+            };
+            Ok(vec![
+                TimeEntry {
+                    id: 1,
+                    project: Some(project(1, Some(acme.clone()))),
+                    ..TimeEntry::default()
+                },
+                TimeEntry {
+                    id: 2,
+                    project: Some(project(2, Some(create_client(4, "Initech")))),
+                    ..TimeEntry::default()
+                },
+                TimeEntry {
+                    id: 3,
+                    project: Some(project(3, None)),
+                    ..TimeEntry::default()
+                },
+                TimeEntry {
+                    id: 4,
+                    ..TimeEntry::default()
+                },
+            ])
+        });
+        api_client.expect_get_clients().returning(|| {
+            Ok([
+                (3, create_client(3, "Acme")),
+                (4, create_client(4, "Initech")),
+            ]
+            .into())
+        });
+        api_client
+    }
+
+    #[tokio::test]
+    async fn fetch_errors_are_returned_to_map_the_exit_code() {
+        let mut api_client = MockApiClient::new();
+        api_client
+            .expect_get_time_entries()
+            .returning(|_| Err(Box::new(ApiError::Network)));
+
+        let error = ListCommand::execute(api_client, None, DateRange::default(), None, None)
+            .await
+            .unwrap_err();
+
+        assert!(error::is_network_error(error.as_ref()));
+    }
+
+    #[tokio::test]
+    async fn only_entries_of_projects_of_the_client_are_kept() {
+        let api_client = create_api_client();
+
+        let time_entries = get_filtered_time_entries(
+            &api_client,
+            DateRange::default(),
+            None,
+            Some("Acme".to_string()),
+        )
+        .await
+        .unwrap();
+
+        let ids: Vec<i64> = time_entries.iter().map(|te| te.id).collect();
+        assert_eq!(ids, vec![1]);
+    }
+
+    #[tokio::test]
+    async fn unknown_clients_are_reported() {
+        let api_client = create_api_client();
+
+        let error = get_filtered_time_entries(
+            &api_client,
+            DateRange::default(),
+            None,
+            Some("Globex".to_string()),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<ArgumentError>(),
+            Some(ArgumentError::ClientNotFound(_))
+        ));
+    }
+}
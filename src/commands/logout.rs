@@ -0,0 +1,70 @@
+use crate::credentials;
+use crate::models;
+use crate::output;
+use colored::Colorize;
+use credentials::{CredentialsStorage, EnvironmentStorage};
+use models::ResultWithDefaultError;
+
+pub struct LogoutCommand;
+
+impl LogoutCommand {
+    pub async fn execute(
+        credentials_storage: impl CredentialsStorage,
+        has_token_command: bool,
+    ) -> ResultWithDefaultError<()> {
+        match credentials_storage.delete() {
+            Ok(()) => output::print_message("Successfully logged out".green()),
+            Err(err) if credentials::is_missing(err.as_ref()) => {
+                output::print_message("No stored API token was found".yellow())
+            }
+            Err(err) => return Err(err),
+        }
+        // These can't be deleted, and running the token_command might prompt.
+        if EnvironmentStorage.read().is_ok() {
+            output::print_message(
+                "TOGGL_API_TOKEN still provides an API token, unset it to log out completely"
+                    .yellow(),
+            );
+        }
+        if has_token_command {
+            output::print_message(
+                "The token_command of the global config still provides an API token, remove it to log out completely"
+                    .yellow(),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::StorageError;
+    use credentials::MockCredentialsStorage;
+
+    #[tokio::test]
+    async fn logging_out_without_a_stored_token_is_not_an_error() {
+        let mut credentials_storage = MockCredentialsStorage::new();
+        credentials_storage
+            .expect_delete()
+            .returning(|| Err(Box::new(StorageError::Missing)));
+
+        assert!(LogoutCommand::execute(credentials_storage, false)
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn failing_to_delete_the_token_returns_an_error() {
+        let mut credentials_storage = MockCredentialsStorage::new();
+        credentials_storage
+            .expect_delete()
+            .returning(|| Err(Box::new(StorageError::Write)));
+        credentials_storage.expect_read().never();
+
+        assert!(LogoutCommand::execute(credentials_storage, false)
+            .await
+            .is_err());
+    }
+}
@@ -1,6 +1,18 @@
+pub mod add;
 pub mod auth;
+pub mod cache;
+pub mod clients;
 pub mod cont;
+pub mod delete;
+pub mod edit;
 pub mod list;
+pub mod logout;
+pub mod projects;
+pub mod report;
 pub mod running;
 pub mod start;
 pub mod stop;
+pub mod sync;
+pub mod tags;
+pub mod tasks;
+pub mod whoami;
@@ -0,0 +1,228 @@
+use crate::api;
+use crate::commands;
+use crate::config;
+use crate::error;
+use crate::models;
+use crate::output;
+use api::client::ApiClient;
+use colored::Colorize;
+use commands::clients::find_client;
+use config::model::BranchConfig;
+use error::ArgumentError;
+use models::{Project, ResultWithDefaultError};
+use std::collections::HashMap;
+
+pub struct ProjectsListCommand;
+pub struct ProjectsCreateCommand;
+pub struct ProjectsArchiveCommand;
+pub struct ProjectsRenameCommand;
+
+pub async fn find_project(
+    api_client: &impl ApiClient,
+    workspace_id: i64,
+    name: String,
+) -> ResultWithDefaultError<Project> {
+    let projects = api_client.get_projects().await?;
+    projects
+        .into_values()
+        .find(|p| p.name == name && p.workspace_id == workspace_id && p.active)
+        .ok_or_else(|| ArgumentError::ProjectNotFound(name).into())
+}
+
+async fn create_project(
+    api_client: &impl ApiClient,
+    workspace_id: i64,
+    name: String,
+    client: Option<String>,
+) -> ResultWithDefaultError<Project> {
+    let client_id = match client {
+        None => None,
+        Some(name) => Some(find_client(api_client, workspace_id, name).await?.id),
+    };
+    let id = api_client
+        .create_project(workspace_id, name.clone(), client_id)
+        .await?;
+    // Fetch the project again to get what Toggl filled in, e.g. its color.
+    let mut projects = api_client.get_projects().await?;
+    projects
+        .remove(&id)
+        .ok_or_else(|| ArgumentError::ProjectNotFound(name).into())
+}
+
+/// Creates the project of the config if it doesn't exist yet, provided the
+/// config opted in with `create_project`, and adds it to `projects`.
+pub async fn create_missing_project(
+    api_client: &impl ApiClient,
+    config: &BranchConfig,
+    workspace_id: i64,
+    projects: &mut HashMap<i64, Project>,
+) -> ResultWithDefaultError<()> {
+    let name = match &config.project {
+        Some(name) if config.create_project => name,
+        _ => return Ok(()),
+    };
+    if name.is_empty() || projects.values().any(|p| &p.name == name) {
+        return Ok(());
+    }
+
+    let project = create_project(
+        api_client,
+        workspace_id,
+        name.clone(),
+        config.client.clone(),
+    )
+    .await?;
+    output::print_message(format!("{} {}", "Created project".green(), project));
+    projects.insert(project.id, project);
+
+    Ok(())
+}
+
+impl ProjectsListCommand {
+    pub async fn execute(
+        api_client: impl ApiClient,
+        workspace: Option<String>,
+        client: Option<String>,
+    ) -> ResultWithDefaultError<()> {
+        let workspace_id = api_client.get_workspace_id(workspace).await?;
+        let client_id = match client {
+            None => None,
+            Some(name) => Some(find_client(&api_client, workspace_id, name).await?.id),
+        };
+        let mut projects: Vec<Project> = api_client
+            .get_projects()
+            .await?
+            .into_values()
+            .filter(|p| p.workspace_id == workspace_id && p.active)
+            .filter(|p| client_id.is_none() || p.client.as_ref().map(|c| c.id) == client_id)
+            .collect();
+        if projects.is_empty() {
+            output::print_message("No projects found".yellow());
+        }
+        projects.sort_by_key(|p| p.name.to_lowercase());
+        output::print_items(projects.iter());
+
+        Ok(())
+    }
+}
+
+impl ProjectsCreateCommand {
+    pub async fn execute(
+        api_client: impl ApiClient,
+        workspace: Option<String>,
+        name: String,
+        client: Option<String>,
+    ) -> ResultWithDefaultError<()> {
+        let workspace_id = api_client.get_workspace_id(workspace).await?;
+        let project = create_project(&api_client, workspace_id, name, client).await?;
+        output::print_message("Project created".green());
+        output::print_item(Some(&project));
+
+        Ok(())
+    }
+}
+
+impl ProjectsArchiveCommand {
+    pub async fn execute(
+        api_client: impl ApiClient,
+        workspace: Option<String>,
+        name: String,
+    ) -> ResultWithDefaultError<()> {
+        let workspace_id = api_client.get_workspace_id(workspace).await?;
+        let project = find_project(&api_client, workspace_id, name).await?;
+        let archived_project = Project {
+            active: false,
+            ..project
+        };
+        api_client.update_project(archived_project.clone()).await?;
+        output::print_message("Project archived".green());
+        output::print_item(Some(&archived_project));
+
+        Ok(())
+    }
+}
+
+impl ProjectsRenameCommand {
+    pub async fn execute(
+        api_client: impl ApiClient,
+        workspace: Option<String>,
+        name: String,
+        new_name: String,
+    ) -> ResultWithDefaultError<()> {
+        let workspace_id = api_client.get_workspace_id(workspace).await?;
+        let project = find_project(&api_client, workspace_id, name).await?;
+        let renamed_project = Project {
+            name: new_name,
+            ..project
+        };
+        api_client.update_project(renamed_project.clone()).await?;
+        output::print_message("Project renamed".green());
+        output::print_item(Some(&renamed_project));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::client::MockApiClient;
+    use crate::models::Client;
+
+    fn create_config(create_project: bool) -> BranchConfig {
+        BranchConfig {
+            project: Some("toggl-cli".to_string()),
+            client: Some("Acme".to_string()),
+            create_project,
+            ..BranchConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn missing_projects_are_created_under_the_client_of_the_config() {
+        let acme = Client {
+            id: 3,
+            name: "Acme".to_string(),
+            workspace_id: 1,
+            archived: false,
+        };
+        let mut api_client = MockApiClient::new();
+        let clients: HashMap<i64, Client> = [(acme.id, acme.clone())].into();
+        api_client
+            .expect_get_clients()
+            .returning(move || Ok(clients.clone()));
+        api_client
+            .expect_create_project()
+            .withf(|workspace_id, name, client_id| {
+                *workspace_id == 1 && name == "toggl-cli" && *client_id == Some(3)
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(5));
+        let created_project = Project {
+            client: Some(acme),
+            ..Project::fixture(5, "toggl-cli")
+        };
+        api_client
+            .expect_get_projects()
+            .returning(move || Ok([(5, created_project.clone())].into()));
+        let mut projects = HashMap::new();
+
+        create_missing_project(&api_client, &create_config(true), 1, &mut projects)
+            .await
+            .unwrap();
+
+        assert_eq!(projects[&5].client.as_ref().unwrap().name, "Acme");
+    }
+
+    #[tokio::test]
+    async fn projects_are_only_created_when_the_config_opts_in() {
+        let api_client = MockApiClient::new();
+        let mut projects = HashMap::new();
+
+        create_missing_project(&api_client, &create_config(false), 1, &mut projects)
+            .await
+            .unwrap();
+
+        assert!(projects.is_empty());
+    }
+}
@@ -0,0 +1,252 @@
+use crate::api;
+use crate::arguments::ReportGrouping;
+use crate::commands::list::get_filtered_time_entries;
+use crate::constants;
+use crate::models;
+use crate::output;
+use api::client::ApiClient;
+use chrono::Duration;
+use colored::Colorize;
+use models::{format_duration_hmmss, DateRange, ResultWithDefaultError, TimeEntry};
+use output::{OutputFormat, Tabular};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::collections::HashMap;
+
+pub struct ReportCommand;
+
+#[derive(Debug, PartialEq)]
+struct ReportRow {
+    label: String,
+    billable: Duration,
+    non_billable: Duration,
+}
+
+impl ReportRow {
+    fn new(label: String) -> Self {
+        Self {
+            label,
+            billable: Duration::zero(),
+            non_billable: Duration::zero(),
+        }
+    }
+
+    fn add(&mut self, time_entry: &TimeEntry) {
+        if time_entry.billable {
+            self.billable = self.billable + time_entry.get_duration();
+        } else {
+            self.non_billable = self.non_billable + time_entry.get_duration();
+        }
+    }
+
+    fn total(&self) -> Duration {
+        self.billable + self.non_billable
+    }
+}
+
+impl std::fmt::Display for ReportRow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.label, format_duration_hmmss(self.total()))
+    }
+}
+
+impl Serialize for ReportRow {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut row = serializer.serialize_struct("ReportRow", 4)?;
+        row.serialize_field("label", &self.label)?;
+        row.serialize_field("billable", &self.billable.num_seconds())?;
+        row.serialize_field("non_billable", &self.non_billable.num_seconds())?;
+        row.serialize_field("total", &self.total().num_seconds())?;
+        row.end()
+    }
+}
+
+impl Tabular for ReportRow {
+    fn headers() -> Vec<&'static str> {
+        vec!["label", "billable", "non_billable", "total"]
+    }
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.label.clone(),
+            self.billable.num_seconds().to_string(),
+            self.non_billable.num_seconds().to_string(),
+            self.total().num_seconds().to_string(),
+        ]
+    }
+}
+
+impl ReportCommand {
+    pub async fn execute(
+        api_client: impl ApiClient,
+        grouping: ReportGrouping,
+        range: DateRange,
+        workspace: Option<String>,
+        client: Option<String>,
+    ) -> ResultWithDefaultError<()> {
+        let range = if range == DateRange::default() {
+            DateRange::this_week()
+        } else {
+            range
+        };
+        let time_entries = get_filtered_time_entries(&api_client, range, workspace, client).await?;
+        if time_entries.is_empty() {
+            output::print_message("No time entries found".yellow());
+            return Ok(());
+        }
+
+        let (rows, total) = summarize(&time_entries, grouping);
+        match output::get_output_format() {
+            OutputFormat::Text => print_report(grouping, &rows, &total),
+            _ => output::print_items(rows.iter().chain([&total])),
+        }
+
+        Ok(())
+    }
+}
+
+fn get_labels(time_entry: &TimeEntry, grouping: ReportGrouping) -> Vec<String> {
+    match grouping {
+        ReportGrouping::Project => vec![time_entry
+            .project
+            .as_ref()
+            .map(|p| p.name.clone())
+            .unwrap_or(constants::NO_PROJECT.to_string())],
+        ReportGrouping::Client => vec![time_entry
+            .project
+            .as_ref()
+            .and_then(|p| p.client.as_ref())
+            .map(|c| c.name.clone())
+            .unwrap_or(constants::NO_CLIENT.to_string())],
+        ReportGrouping::Tag if time_entry.tags.is_empty() => vec![constants::NO_TAGS.to_string()],
+        ReportGrouping::Tag => time_entry.tags.clone(),
+        ReportGrouping::Description => vec![time_entry.get_description()],
+    }
+}
+
+/// Sums up the time entries per group, longest first. Entries with several tags
+/// count towards each of them, so the total is summed up separately.
+fn summarize(time_entries: &[TimeEntry], grouping: ReportGrouping) -> (Vec<ReportRow>, ReportRow) {
+    let mut rows: HashMap<String, ReportRow> = HashMap::new();
+    let mut total = ReportRow::new("Total".to_string());
+    for time_entry in time_entries {
+        for label in get_labels(time_entry, grouping) {
+            rows.entry(label.clone())
+                .or_insert_with(|| ReportRow::new(label))
+                .add(time_entry);
+        }
+        total.add(time_entry);
+    }
+
+    let mut rows: Vec<ReportRow> = rows.into_values().collect();
+    rows.sort_by(|a, b| b.total().cmp(&a.total()).then(a.label.cmp(&b.label)));
+    (rows, total)
+}
+
+fn print_report(grouping: ReportGrouping, rows: &[ReportRow], total: &ReportRow) {
+    let header = match grouping {
+        ReportGrouping::Project => "Project",
+        ReportGrouping::Client => "Client",
+        ReportGrouping::Tag => "Tag",
+        ReportGrouping::Description => "Description",
+    };
+    let width = rows
+        .iter()
+        .map(|row| row.label.chars().count())
+        .chain([header.len()])
+        .max()
+        .unwrap_or_default();
+    let format_row = |label: &str, billable: &str, non_billable: &str, total: &str| {
+        format!(
+            "{:width$}  {:>10}  {:>12}  {:>10}",
+            label, billable, non_billable, total
+        )
+    };
+    let format_report_row = |row: &ReportRow| {
+        format_row(
+            &row.label,
+            &format_duration_hmmss(row.billable),
+            &format_duration_hmmss(row.non_billable),
+            &format_duration_hmmss(row.total()),
+        )
+    };
+
+    println!(
+        "{}",
+        format_row(header, "Billable", "Non-billable", "Total").bold()
+    );
+    for row in rows {
+        println!("{}", format_report_row(row));
+    }
+    println!("{}", format_report_row(total).bold());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time_entry(description: &str, tags: &[&str], billable: bool, minutes: i64) -> TimeEntry {
+        let time_entry = TimeEntry {
+            description: description.to_string(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            billable,
+            ..TimeEntry::default()
+        };
+        time_entry.as_stopped_time_entry(time_entry.start + Duration::minutes(minutes))
+    }
+
+    #[test]
+    fn rows_split_billable_time_and_are_sorted_by_total() {
+        let time_entries = vec![
+            time_entry("Review", &[], true, 30),
+            time_entry("Coding", &[], false, 60),
+            time_entry("Review", &[], false, 15),
+        ];
+
+        let (rows, total) = summarize(&time_entries, ReportGrouping::Description);
+
+        assert_eq!(
+            rows,
+            vec![
+                ReportRow {
+                    label: "Coding".to_string(),
+                    billable: Duration::zero(),
+                    non_billable: Duration::minutes(60),
+                },
+                ReportRow {
+                    label: "Review".to_string(),
+                    billable: Duration::minutes(30),
+                    non_billable: Duration::minutes(15),
+                },
+            ]
+        );
+        assert_eq!(total.total(), Duration::minutes(105));
+    }
+
+    #[test]
+    fn entries_count_towards_each_of_their_tags_but_once_towards_the_total() {
+        let time_entries = vec![
+            time_entry("Meeting", &["internal", "planning"], false, 60),
+            time_entry("Coding", &[], false, 30),
+        ];
+
+        let (rows, total) = summarize(&time_entries, ReportGrouping::Tag);
+
+        let labels: Vec<&str> = rows.iter().map(|row| row.label.as_str()).collect();
+        assert_eq!(labels, vec!["internal", "planning", constants::NO_TAGS]);
+        assert_eq!(total.total(), Duration::minutes(90));
+    }
+
+    #[test]
+    fn entries_without_a_project_are_grouped_together() {
+        let time_entries = vec![
+            time_entry("Coding", &[], false, 30),
+            time_entry("Review", &[], false, 30),
+        ];
+
+        let (rows, _) = summarize(&time_entries, ReportGrouping::Client);
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].label, constants::NO_CLIENT);
+        assert_eq!(rows[0].total(), Duration::hours(1));
+    }
+}
@@ -1,5 +1,6 @@
 use crate::api;
 use crate::models;
+use crate::output;
 use api::client::ApiClient;
 use colored::Colorize;
 use models::ResultWithDefaultError;
@@ -8,11 +9,11 @@ pub struct RunningTimeEntryCommand;
 
 impl RunningTimeEntryCommand {
     pub async fn execute(api_client: impl ApiClient) -> ResultWithDefaultError<()> {
-        let entities = api_client.get_entities().await?;
-        match entities.running_time_entry() {
-            None => println!("{}", "No time entry is running at the moment".yellow()),
-            Some(running_time_entry) => println!("{}", running_time_entry),
+        let running_time_entry = api_client.get_running_time_entry().await?;
+        if running_time_entry.is_none() {
+            output::print_message("No time entry is running at the moment".yellow());
         }
+        output::print_item(running_time_entry.as_ref());
 
         Ok(())
     }
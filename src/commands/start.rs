@@ -1,29 +1,36 @@
 use crate::api;
+use crate::arguments::StartArguments;
 use crate::commands;
 use crate::config;
+use crate::error;
+use crate::journal;
 use crate::models;
-use crate::models::Entities;
 use crate::models::Project;
+use crate::models::Tag;
+use crate::models::Task;
+use crate::output;
 use crate::picker::ItemPicker;
 use crate::picker::PickableItem;
 use crate::picker::PickableItemKind;
 use crate::utilities;
 use api::client::ApiClient;
 use colored::Colorize;
+use commands::projects::create_missing_project;
 use commands::stop::{StopCommand, StopCommandOrigin};
+use error::ArgumentError;
+use journal::{Journal, JournalEntry};
 use models::ResultWithDefaultError;
 use models::TimeEntry;
+use std::collections::HashMap;
 
 pub struct StartCommand;
 
 fn interactively_create_time_entry(
     default_time_entry: TimeEntry,
-    workspace_id: i64,
-    entities: Entities,
+    projects: HashMap<i64, Project>,
+    tasks: HashMap<i64, Task>,
+    tags: HashMap<i64, Tag>,
     picker: Box<dyn ItemPicker>,
-    description: String,
-    project: Option<Project>,
-    billable: bool,
 ) -> TimeEntry {
     let yes_or_default_no = [
         "y".to_string(),
@@ -32,35 +39,26 @@ fn interactively_create_time_entry(
         "".to_string(),
     ];
 
-    let (project, task) = match project {
-        Some(_) => (project, None),
+    let (project, task) = match default_time_entry.project.clone() {
+        Some(project) => (Some(project), None),
         None => {
-            if entities.projects.is_empty() {
+            if projects.is_empty() {
                 (None, None)
             } else {
-                let mut pickable_items: Vec<PickableItem> = entities
-                    .projects
+                let mut pickable_items: Vec<PickableItem> = projects
                     .clone()
                     .into_values()
                     .map(PickableItem::from_project)
                     .collect();
 
-                pickable_items.extend(
-                    entities
-                        .tasks
-                        .clone()
-                        .into_values()
-                        .map(PickableItem::from_task),
-                );
+                pickable_items.extend(tasks.clone().into_values().map(PickableItem::from_task));
 
                 match picker.pick(pickable_items) {
                     Ok(picked_key) => match picked_key.kind {
-                        PickableItemKind::TimeEntry => (None, None),
-                        PickableItemKind::Project => {
-                            (entities.projects.get(&picked_key.id).cloned(), None)
-                        }
+                        PickableItemKind::TimeEntry | PickableItemKind::Tag => (None, None),
+                        PickableItemKind::Project => (projects.get(&picked_key.id).cloned(), None),
                         PickableItemKind::Task => {
-                            let task = entities.tasks.get(&picked_key.id).cloned().unwrap();
+                            let task = tasks.get(&picked_key.id).cloned().unwrap();
                             (Some(task.clone().project), Some(task))
                         }
                     },
@@ -71,8 +69,26 @@ fn interactively_create_time_entry(
         }
     };
 
+    // Without a pick, e.g. when the picker is cancelled, the default tags are kept.
+    let tags = if tags.is_empty() {
+        default_time_entry.tags.clone()
+    } else {
+        let pickable_items = tags
+            .clone()
+            .into_values()
+            .map(PickableItem::from_tag)
+            .collect();
+        match picker.pick_many(pickable_items) {
+            Ok(picked_keys) if !picked_keys.is_empty() => picked_keys
+                .iter()
+                .filter_map(|key| tags.get(&key.id).map(|t| t.name.clone()))
+                .collect(),
+            _ => default_time_entry.tags.clone(),
+        }
+    };
+
     // Only ask for billable if the user didn't provide a value AND if the selected project doesn't have a default billable setting.
-    let billable = billable
+    let billable = default_time_entry.billable
         || project.clone().and_then(|p| p.billable).unwrap_or(
             utilities::read_from_stdin_with_constraints(
                 "Is your time entry billable? (y/N): ",
@@ -84,78 +100,231 @@ fn interactively_create_time_entry(
 
     TimeEntry {
         billable,
-        description,
-        workspace_id,
         project,
         task,
+        tags,
         ..default_time_entry
     }
 }
 
+/// Fills in the description, project and billable flag given on the command line,
+/// falling back to the defaults of the active track config.
+pub fn resolve_time_entry(
+    default_time_entry: TimeEntry,
+    projects: &HashMap<i64, Project>,
+    description: Option<String>,
+    project_name: Option<String>,
+    billable: bool,
+) -> TimeEntry {
+    let project = project_name
+        .and_then(|name| projects.values().find(|p| p.name == name).cloned())
+        .or(default_time_entry.project.clone());
+
+    let billable = billable
+        || default_time_entry.billable
+        || project.clone().and_then(|p| p.billable).unwrap_or(false);
+
+    let description = description.unwrap_or(default_time_entry.description.clone());
+
+    TimeEntry {
+        billable,
+        description,
+        project,
+        ..default_time_entry
+    }
+}
+
+/// Finds the task given on the command line within the project of the time
+/// entry. Without one, the default task only applies if the project wasn't
+/// overridden.
+pub fn resolve_task(
+    time_entry: &TimeEntry,
+    tasks: &HashMap<i64, Task>,
+    task_name: Option<String>,
+) -> Result<Option<Task>, ArgumentError> {
+    let project_id = time_entry.project.as_ref().map(|p| p.id);
+    match task_name {
+        Some(name) => tasks
+            .values()
+            .find(|t| t.name == name && Some(t.project.id) == project_id)
+            .cloned()
+            .map(Some)
+            .ok_or(ArgumentError::TaskNotFound(name)),
+        None => Ok(time_entry
+            .task
+            .clone()
+            .filter(|t| Some(t.project.id) == project_id)),
+    }
+}
+
+/// Drops the projects and tasks of other workspaces, so that names only have to
+/// be unique within a workspace.
+pub fn retain_workspace(
+    workspace_id: i64,
+    projects: &mut HashMap<i64, Project>,
+    tasks: &mut HashMap<i64, Task>,
+) {
+    projects.retain(|_, p| p.workspace_id == workspace_id);
+    tasks.retain(|_, t| t.workspace_id == workspace_id);
+}
+
 impl StartCommand {
     pub async fn execute(
         api_client: impl ApiClient,
         picker: Box<dyn ItemPicker>,
-        description: Option<String>,
-        project_name: Option<String>,
-        billable: bool,
-        interactive: bool,
+        arguments: StartArguments,
+        workspace: Option<String>,
     ) -> ResultWithDefaultError<()> {
-        StopCommand::execute(&api_client, StopCommandOrigin::StartCommand).await?;
-
-        let workspace_id = (api_client.get_user().await?).default_workspace_id;
-        let entities = api_client.get_entities().await?;
+        let start_time = arguments.time.resolve()?;
+        let offline =
+            match StopCommand::execute(&api_client, StopCommandOrigin::StartCommand, start_time)
+                .await
+            {
+                Ok(_) => false,
+                Err(error) if error::is_network_error(error.as_ref()) => true,
+                Err(error) => return Err(error),
+            };
 
         let config_path = config::locate::locate_config_path()?;
         let track_config = config::parser::get_config_from_file(config_path)?;
-        let default_time_entry = track_config.get_default_entry(entities.clone())?;
+        let workspace = workspace.or(track_config.get_active_config()?.workspace.clone());
+        let workspace_id = api_client.get_workspace_id(workspace).await;
+        let workspace_id = match workspace_id {
+            // Entries started offline get the user's default workspace once they are synced.
+            Err(error) if offline && error::is_network_error(error.as_ref()) => None,
+            result => Some(result?),
+        };
+        let mut projects = unless_offline(api_client.get_projects().await, offline)?;
+        let mut tasks = unless_offline(api_client.get_tasks().await, offline)?;
+        if let Some(workspace_id) = workspace_id {
+            retain_workspace(workspace_id, &mut projects, &mut tasks);
+            if !offline {
+                let config = track_config.get_active_config()?;
+                create_missing_project(&api_client, config, workspace_id, &mut projects).await?;
+            }
+        }
 
-        let project = project_name
-            .and_then(|name| {
-                entities
-                    .projects
-                    .clone()
-                    .into_values()
-                    .find(|p| p.name == name)
-            })
-            .or(default_time_entry.project.clone());
-
-        let billable = billable
-            || default_time_entry.billable
-            || project.clone().and_then(|p| p.billable).unwrap_or(false);
-
-        let description = description.unwrap_or(default_time_entry.description.clone());
-
-        let time_entry_to_create = if interactive {
-            interactively_create_time_entry(
-                default_time_entry,
-                workspace_id,
-                entities,
-                picker,
-                description,
-                project,
-                billable,
-            )
+        let default_time_entry = track_config
+            .get_default_entry(workspace_id, &projects, &tasks)?
+            .as_running_time_entry(start_time);
+        let resolved_time_entry = resolve_time_entry(
+            default_time_entry,
+            &projects,
+            arguments.description,
+            arguments.project,
+            arguments.billable,
+        );
+
+        let task = resolve_task(&resolved_time_entry, &tasks, arguments.task)?;
+        let tags = if arguments.tags.is_empty() {
+            resolved_time_entry.tags.clone()
         } else {
-            TimeEntry {
-                billable,
-                description,
-                project,
-                workspace_id,
-                ..default_time_entry
-            }
+            arguments.tags.clone()
+        };
+        let resolved_time_entry = TimeEntry {
+            task,
+            tags,
+            ..resolved_time_entry
         };
 
-        let started_entry_id = api_client.create_time_entry(time_entry_to_create).await?;
-        let entities = api_client.get_entities().await?;
-        let started_entry = entities
-            .time_entries
-            .iter()
-            .find(|te| te.id == started_entry_id)
-            .unwrap();
+        let time_entry_to_create = if arguments.interactive {
+            // Tags given on the command line aren't picked again.
+            let tags = match workspace_id {
+                Some(workspace_id) if arguments.tags.is_empty() => {
+                    unless_offline(api_client.get_tags(workspace_id).await, offline)?
+                }
+                _ => HashMap::new(),
+            };
+            interactively_create_time_entry(resolved_time_entry, projects, tasks, tags, picker)
+        } else {
+            resolved_time_entry
+        };
 
-        println!("{}\n{}", "Time entry started".green(), started_entry);
+        let started_entry_id = if offline {
+            Err(Box::new(error::ApiError::Network).into())
+        } else {
+            api_client
+                .create_time_entry(time_entry_to_create.clone())
+                .await
+        };
+        match started_entry_id {
+            Err(error) if error::is_network_error(error.as_ref()) => {
+                Journal::default().append(JournalEntry::Start {
+                    time_entry: Box::new(time_entry_to_create.clone()),
+                    uses_default_workspace: workspace_id.is_none(),
+                })?;
+                output::print_message("You're offline, the time entry will be started once you're back online. Run toggl sync to do it manually.".yellow());
+                output::print_item(Some(&time_entry_to_create));
+            }
+            Err(error) => return Err(error),
+            Ok(started_entry_id) => {
+                let started_entry = TimeEntry {
+                    id: started_entry_id,
+                    ..time_entry_to_create
+                };
+                output::print_message("Time entry started".green());
+                output::print_item(Some(&started_entry));
+            }
+        }
 
         Ok(())
     }
 }
+
+/// While offline, missing projects and tasks shouldn't keep us from tracking time.
+fn unless_offline<T: Default>(
+    result: ResultWithDefaultError<T>,
+    offline: bool,
+) -> ResultWithDefaultError<T> {
+    match result {
+        Err(error) if offline && error::is_network_error(error.as_ref()) => Ok(T::default()),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_tasks() -> HashMap<i64, Task> {
+        [(10, 1), (20, 2)]
+            .into_iter()
+            .map(|(id, project_id)| {
+                let task = Task {
+                    id,
+                    name: "Development".to_string(),
+                    workspace_id: 1,
+                    project: Project::fixture(project_id, "Project"),
+                };
+                (id, task)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tasks_are_resolved_within_the_project_of_the_entry() {
+        let time_entry = TimeEntry {
+            project: Some(Project::fixture(2, "Project")),
+            ..TimeEntry::default()
+        };
+
+        let task = resolve_task(
+            &time_entry,
+            &create_tasks(),
+            Some("Development".to_string()),
+        );
+
+        assert_eq!(task.unwrap().unwrap().id, 20);
+    }
+
+    #[test]
+    fn tasks_require_a_project() {
+        let task = resolve_task(
+            &TimeEntry::default(),
+            &create_tasks(),
+            Some("Development".to_string()),
+        );
+
+        assert!(matches!(task, Err(ArgumentError::TaskNotFound(_))));
+    }
+}
@@ -1,8 +1,14 @@
 use crate::api;
+use crate::error;
+use crate::journal;
 use crate::models;
+use crate::output;
+use crate::timezone;
 use api::client::ApiClient;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use colored::Colorize;
+use error::ArgumentError;
+use journal::{Journal, JournalEntry};
 use models::{ResultWithDefaultError, TimeEntry};
 
 pub struct StopCommand;
@@ -17,13 +23,35 @@ impl StopCommand {
     pub async fn execute(
         api_client: &impl ApiClient,
         origin: StopCommandOrigin,
+        stop_time: DateTime<Utc>,
     ) -> ResultWithDefaultError<Option<TimeEntry>> {
-        let entities = api_client.get_entities().await?;
-        match entities.running_time_entry() {
+        let running_time_entry = match api_client.get_running_time_entry().await {
+            Err(error)
+                if error::is_network_error(error.as_ref())
+                    && matches!(origin, StopCommandOrigin::CommandLine) =>
+            {
+                Journal::default().append(JournalEntry::Stop { stop: stop_time })?;
+                output::print_message(format!(
+                    "{} {}\n{}",
+                    "You're offline, the running time entry will be stopped at".yellow(),
+                    stop_time
+                        .with_timezone(&timezone::get_timezone())
+                        .format("%H:%M")
+                        .to_string()
+                        .bold(),
+                    "once you're back online. Run toggl sync to do it manually.".yellow()
+                ));
+                output::print_item::<TimeEntry>(None);
+                return Ok(None);
+            }
+            result => result?,
+        };
+        match running_time_entry {
             None => {
                 match origin {
                     StopCommandOrigin::CommandLine => {
-                        println!("{}", "No time entry is running at the moment".yellow())
+                        output::print_message("No time entry is running at the moment".yellow());
+                        output::print_item::<TimeEntry>(None);
                     }
                     StopCommandOrigin::StartCommand => (),
                     StopCommandOrigin::ContinueCommand => (),
@@ -32,20 +60,29 @@ impl StopCommand {
                 Ok(None)
             }
             Some(running_time_entry) => {
-                let stop_time = Utc::now();
+                if stop_time < running_time_entry.start {
+                    return Err(Box::new(ArgumentError::StopBeforeStart));
+                }
                 let stopped_time_entry = running_time_entry.as_stopped_time_entry(stop_time);
                 api_client
                     .update_time_entry(stopped_time_entry.clone())
                     .await?;
 
-                let message = match origin {
-                    StopCommandOrigin::CommandLine => "Time entry stopped successfully".green(),
-                    StopCommandOrigin::StartCommand => "Running time entry stopped".yellow(),
-                    StopCommandOrigin::ContinueCommand => "Running time entry stopped".yellow(),
+                match origin {
+                    StopCommandOrigin::CommandLine => {
+                        output::print_message("Time entry stopped successfully".green());
+                        output::print_item(Some(&stopped_time_entry));
+                    }
+                    // Only the entry that was started or continued is part of the output.
+                    StopCommandOrigin::StartCommand | StopCommandOrigin::ContinueCommand => {
+                        output::print_message(format!(
+                            "{}\n{}",
+                            "Running time entry stopped".yellow(),
+                            stopped_time_entry
+                        ));
+                    }
                 };
 
-                println!("{}\n{}", message, stopped_time_entry.clone());
-
                 Ok(Some(stopped_time_entry))
             }
         }
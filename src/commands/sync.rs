@@ -0,0 +1,245 @@
+use crate::api;
+use crate::error;
+use crate::journal;
+use crate::models;
+use crate::timezone;
+use api::client::ApiClient;
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use journal::{Journal, JournalEntry};
+use models::{ResultWithDefaultError, TimeEntry};
+
+pub struct SyncCommand;
+
+pub enum SyncCommandOrigin {
+    CommandLine,
+    PendingOperations,
+}
+
+enum ReplayOutcome {
+    Synced(String, Box<TimeEntry>),
+    Conflict(String),
+}
+
+impl SyncCommand {
+    /// Replays the operations recorded while offline in the order they happened.
+    /// Operations that no longer make sense server-side are reported and dropped,
+    /// everything after a failing operation is kept for the next attempt.
+    pub async fn execute(
+        api_client: &impl ApiClient,
+        journal: &Journal,
+        origin: SyncCommandOrigin,
+    ) -> ResultWithDefaultError<()> {
+        let mut pending_entries = journal.read()?;
+        if pending_entries.is_empty() {
+            if let SyncCommandOrigin::CommandLine = origin {
+                println!("{}", "No pending operations to sync".yellow());
+            }
+            return Ok(());
+        }
+
+        while let Some(entry) = pending_entries.first().cloned() {
+            match replay(api_client, entry).await {
+                Err(error) => {
+                    return match origin {
+                        // We're still offline, the current command takes care of it.
+                        SyncCommandOrigin::PendingOperations
+                            if error::is_network_error(error.as_ref()) =>
+                        {
+                            Ok(())
+                        }
+                        _ => Err(error),
+                    };
+                }
+                Ok(ReplayOutcome::Synced(message, time_entry)) => {
+                    println!("{}\n{}", message.green(), time_entry)
+                }
+                Ok(ReplayOutcome::Conflict(message)) => println!("{}", message.yellow()),
+            }
+            pending_entries.remove(0);
+            journal.write(&pending_entries)?;
+        }
+
+        Ok(())
+    }
+}
+
+async fn replay(
+    api_client: &impl ApiClient,
+    entry: JournalEntry,
+) -> ResultWithDefaultError<ReplayOutcome> {
+    let running_time_entry = api_client.get_running_time_entry().await?;
+    match entry {
+        JournalEntry::Start {
+            time_entry,
+            uses_default_workspace,
+        } => {
+            if let Some(running_time_entry) = running_time_entry {
+                if running_time_entry.start > time_entry.start {
+                    return Ok(ReplayOutcome::Conflict(format!(
+                        "Skipped starting \"{}\" at {}, \"{}\" was started after it",
+                        time_entry.get_description(),
+                        format_time(time_entry.start),
+                        running_time_entry.get_description()
+                    )));
+                }
+                api_client
+                    .update_time_entry(running_time_entry.as_stopped_time_entry(time_entry.start))
+                    .await?;
+            }
+            let time_entry = if uses_default_workspace {
+                let workspace_id = match &time_entry.project {
+                    Some(project) => project.workspace_id,
+                    None => api_client.get_user().await?.default_workspace_id,
+                };
+                TimeEntry {
+                    workspace_id,
+                    ..*time_entry
+                }
+            } else {
+                *time_entry
+            };
+            let id = api_client.create_time_entry(time_entry.clone()).await?;
+            Ok(ReplayOutcome::Synced(
+                format!("Time entry started at {}", format_time(time_entry.start)),
+                Box::new(TimeEntry { id, ..time_entry }),
+            ))
+        }
+        JournalEntry::Stop { stop } => match running_time_entry {
+            None => Ok(ReplayOutcome::Conflict(format!(
+                "Skipped stopping at {}, no time entry is running anymore",
+                format_time(stop)
+            ))),
+            Some(running_time_entry) if running_time_entry.start > stop => {
+                Ok(ReplayOutcome::Conflict(format!(
+                    "Skipped stopping at {}, \"{}\" was started after it",
+                    format_time(stop),
+                    running_time_entry.get_description()
+                )))
+            }
+            Some(running_time_entry) => {
+                let stopped_time_entry = running_time_entry.as_stopped_time_entry(stop);
+                api_client
+                    .update_time_entry(stopped_time_entry.clone())
+                    .await?;
+                Ok(ReplayOutcome::Synced(
+                    format!("Time entry stopped at {}", format_time(stop)),
+                    Box::new(stopped_time_entry),
+                ))
+            }
+        },
+    }
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.with_timezone(&timezone::get_timezone())
+        .format("%Y-%m-%d %H:%M")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::client::MockApiClient;
+    use crate::models::User;
+    use chrono::Duration;
+
+    fn create_journal(name: &str, entries: &[JournalEntry]) -> Journal {
+        let path = std::env::temp_dir().join(format!(
+            "toggl-cli-journal-test-{}-{}.json",
+            name,
+            std::process::id()
+        ));
+        let journal = Journal::new(path);
+        journal.write(entries).unwrap();
+        journal
+    }
+
+    #[tokio::test]
+    async fn stopping_without_a_running_entry_is_reported_and_dropped() {
+        let journal = create_journal("stop", &[JournalEntry::Stop { stop: Utc::now() }]);
+        let mut api_client = MockApiClient::new();
+        api_client
+            .expect_get_running_time_entry()
+            .returning(|| Ok(None));
+        api_client.expect_update_time_entry().never();
+
+        let result =
+            SyncCommand::execute(&api_client, &journal, SyncCommandOrigin::CommandLine).await;
+
+        assert!(result.is_ok());
+        assert!(journal.read().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn starting_stops_the_running_entry_when_the_queued_one_started() {
+        let start = Utc::now() - Duration::minutes(5);
+        let time_entry = TimeEntry {
+            workspace_id: 1,
+            ..TimeEntry::default().as_running_time_entry(start)
+        };
+        let journal = create_journal(
+            "start",
+            &[JournalEntry::Start {
+                time_entry: Box::new(time_entry),
+                uses_default_workspace: false,
+            }],
+        );
+        let mut api_client = MockApiClient::new();
+        api_client.expect_get_running_time_entry().returning(|| {
+            Ok(Some(
+                TimeEntry::default().as_running_time_entry(Utc::now() - Duration::hours(1)),
+            ))
+        });
+        api_client
+            .expect_update_time_entry()
+            .withf(move |te| te.stop == Some(start))
+            .times(1)
+            .returning(|te| Ok(te.id));
+        api_client
+            .expect_create_time_entry()
+            .times(1)
+            .returning(|_| Ok(2));
+
+        let result =
+            SyncCommand::execute(&api_client, &journal, SyncCommandOrigin::CommandLine).await;
+
+        assert!(result.is_ok());
+        assert!(journal.read().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn entries_started_without_a_workspace_get_the_default_one() {
+        let journal = create_journal(
+            "default-workspace",
+            &[JournalEntry::Start {
+                time_entry: Box::new(TimeEntry::default().as_running_time_entry(Utc::now())),
+                uses_default_workspace: true,
+            }],
+        );
+        let mut api_client = MockApiClient::new();
+        api_client
+            .expect_get_running_time_entry()
+            .returning(|| Ok(None));
+        api_client.expect_get_user().returning(|| {
+            Ok(User {
+                api_token: "token".to_string(),
+                email: "toggl@user.org".to_string(),
+                fullname: None,
+                timezone: "UTC".to_string(),
+                default_workspace_id: 3,
+            })
+        });
+        api_client
+            .expect_create_time_entry()
+            .withf(|te| te.workspace_id == 3)
+            .times(1)
+            .returning(|_| Ok(2));
+
+        let result =
+            SyncCommand::execute(&api_client, &journal, SyncCommandOrigin::CommandLine).await;
+
+        assert!(result.is_ok());
+        assert!(journal.read().unwrap().is_empty());
+    }
+}
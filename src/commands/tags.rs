@@ -0,0 +1,176 @@
+use crate::api;
+use crate::error;
+use crate::models;
+use crate::output;
+use crate::utilities;
+use api::client::ApiClient;
+use colored::Colorize;
+use error::ArgumentError;
+use models::{ResultWithDefaultError, Tag};
+
+pub struct TagsListCommand;
+pub struct TagsCreateCommand;
+pub struct TagsRenameCommand;
+pub struct TagsDeleteCommand;
+
+async fn find_tag(
+    api_client: &impl ApiClient,
+    workspace_id: i64,
+    name: String,
+) -> ResultWithDefaultError<Tag> {
+    let tags = api_client.get_tags(workspace_id).await?;
+    tags.into_values()
+        .find(|t| t.name == name)
+        .ok_or_else(|| ArgumentError::TagNotFound(name).into())
+}
+
+impl TagsListCommand {
+    pub async fn execute(
+        api_client: impl ApiClient,
+        workspace: Option<String>,
+    ) -> ResultWithDefaultError<()> {
+        let workspace_id = api_client.get_workspace_id(workspace).await?;
+        let mut tags: Vec<Tag> = api_client
+            .get_tags(workspace_id)
+            .await?
+            .into_values()
+            .collect();
+        if tags.is_empty() {
+            output::print_message("No tags found".yellow());
+        }
+        tags.sort_by_key(|t| t.name.to_lowercase());
+        output::print_items(tags.iter());
+
+        Ok(())
+    }
+}
+
+impl TagsCreateCommand {
+    pub async fn execute(
+        api_client: impl ApiClient,
+        workspace: Option<String>,
+        name: String,
+    ) -> ResultWithDefaultError<()> {
+        let workspace_id = api_client.get_workspace_id(workspace).await?;
+        let id = api_client.create_tag(workspace_id, name.clone()).await?;
+        output::print_message("Tag created".green());
+        output::print_item(Some(&Tag {
+            id,
+            name,
+            workspace_id,
+        }));
+
+        Ok(())
+    }
+}
+
+impl TagsRenameCommand {
+    pub async fn execute(
+        api_client: impl ApiClient,
+        workspace: Option<String>,
+        name: String,
+        new_name: String,
+    ) -> ResultWithDefaultError<()> {
+        let workspace_id = api_client.get_workspace_id(workspace).await?;
+        let tag = find_tag(&api_client, workspace_id, name).await?;
+        let renamed_tag = Tag {
+            name: new_name,
+            ..tag
+        };
+        api_client.update_tag(renamed_tag.clone()).await?;
+        output::print_message("Tag renamed".green());
+        output::print_item(Some(&renamed_tag));
+
+        Ok(())
+    }
+}
+
+impl TagsDeleteCommand {
+    pub async fn execute(
+        api_client: impl ApiClient,
+        workspace: Option<String>,
+        name: String,
+        skip_confirmation: bool,
+    ) -> ResultWithDefaultError<()> {
+        let workspace_id = api_client.get_workspace_id(workspace).await?;
+        let tag = find_tag(&api_client, workspace_id, name).await?;
+
+        let yes_or_default_no = [
+            "y".to_string(),
+            "n".to_string(),
+            "N".to_string(),
+            "".to_string(),
+        ];
+        if !skip_confirmation
+            && utilities::read_from_stdin_with_constraints(
+                &format!(
+                    "Delete the tag {} and remove it from all time entries? (y/N): ",
+                    tag.name
+                ),
+                &yes_or_default_no,
+            ) != "y"
+        {
+            output::print_message("The tag wasn't deleted".yellow());
+            return Ok(());
+        }
+
+        api_client.delete_tag(tag).await?;
+        output::print_message("Tag deleted".green());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::client::MockApiClient;
+    use crate::api::models::NetworkTag;
+
+    #[tokio::test]
+    async fn renaming_keeps_the_id_and_workspace_of_the_tag() {
+        let mut api_client = MockApiClient::new();
+        api_client.expect_get_workspace_id().returning(|_| Ok(1));
+        api_client.expect_get_tags().returning(|workspace_id| {
+            let tag = NetworkTag {
+                id: 7,
+                name: "meeting".to_string(),
+                workspace_id,
+            };
+            Ok([(tag.id, tag.to_tag())].into())
+        });
+        api_client
+            .expect_update_tag()
+            .withf(|tag| tag.id == 7 && tag.workspace_id == 1 && tag.name == "meetings")
+            .times(1)
+            .returning(|tag| Ok(tag.id));
+
+        let result = TagsRenameCommand::execute(
+            api_client,
+            None,
+            "meeting".to_string(),
+            "meetings".to_string(),
+        )
+        .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn unknown_tags_are_reported() {
+        let mut api_client = MockApiClient::new();
+        api_client.expect_get_workspace_id().returning(|_| Ok(1));
+        api_client
+            .expect_get_tags()
+            .returning(|_| Ok(Default::default()));
+
+        let error = TagsDeleteCommand::execute(api_client, None, "unknown".to_string(), true)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<ArgumentError>(),
+            Some(ArgumentError::TagNotFound(name)) if name == "unknown"
+        ));
+    }
+}
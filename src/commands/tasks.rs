@@ -0,0 +1,127 @@
+use crate::api;
+use crate::commands;
+use crate::models;
+use crate::output;
+use api::client::ApiClient;
+use colored::Colorize;
+use commands::projects::find_project;
+use models::{ResultWithDefaultError, Task};
+
+pub struct TasksListCommand;
+pub struct TasksCreateCommand;
+
+impl TasksListCommand {
+    pub async fn execute(
+        api_client: impl ApiClient,
+        workspace: Option<String>,
+        project: Option<String>,
+    ) -> ResultWithDefaultError<()> {
+        let workspace_id = api_client.get_workspace_id(workspace).await?;
+        let project_id = match project {
+            None => None,
+            Some(name) => Some(find_project(&api_client, workspace_id, name).await?.id),
+        };
+        let mut tasks: Vec<Task> = api_client
+            .get_tasks()
+            .await?
+            .into_values()
+            .filter(|t| t.workspace_id == workspace_id)
+            .filter(|t| project_id.map_or(true, |id| t.project.id == id))
+            .collect();
+        if tasks.is_empty() {
+            output::print_message("No tasks found".yellow());
+        }
+        tasks.sort_by_key(|t| (t.project.name.to_lowercase(), t.name.to_lowercase()));
+        output::print_items(tasks.iter());
+
+        Ok(())
+    }
+}
+
+impl TasksCreateCommand {
+    pub async fn execute(
+        api_client: impl ApiClient,
+        workspace: Option<String>,
+        name: String,
+        project: String,
+    ) -> ResultWithDefaultError<()> {
+        let workspace_id = api_client.get_workspace_id(workspace).await?;
+        let project = find_project(&api_client, workspace_id, project).await?;
+        let id = api_client
+            .create_task(project.clone(), name.clone())
+            .await?;
+        output::print_message("Task created".green());
+        output::print_item(Some(&Task {
+            id,
+            name,
+            workspace_id,
+            project,
+        }));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::client::MockApiClient;
+    use crate::error::ArgumentError;
+    use crate::models::Project;
+
+    #[tokio::test]
+    async fn tasks_are_not_created_in_archived_projects() {
+        let mut api_client = MockApiClient::new();
+        api_client.expect_get_workspace_id().returning(|_| Ok(1));
+        api_client.expect_get_projects().returning(|| {
+            let project = Project {
+                active: false,
+                ..Project::fixture(2, "toggl-cli")
+            };
+            Ok([(project.id, project)].into())
+        });
+        api_client.expect_create_task().never();
+
+        let result = TasksCreateCommand::execute(
+            api_client,
+            None,
+            "Development".to_string(),
+            "toggl-cli".to_string(),
+        )
+        .await;
+
+        assert!(matches!(
+            result.err().unwrap().downcast_ref::<ArgumentError>(),
+            Some(ArgumentError::ProjectNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn tasks_are_created_in_the_project_of_the_workspace() {
+        let mut api_client = MockApiClient::new();
+        api_client.expect_get_workspace_id().returning(|_| Ok(1));
+        api_client.expect_get_projects().returning(|| {
+            let other_workspace = Project {
+                workspace_id: 3,
+                ..Project::fixture(4, "toggl-cli")
+            };
+            let project = Project::fixture(2, "toggl-cli");
+            Ok([(4, other_workspace), (2, project)].into())
+        });
+        api_client
+            .expect_create_task()
+            .withf(|project, name| project.id == 2 && name == "Development")
+            .times(1)
+            .returning(|_, _| Ok(10));
+
+        let result = TasksCreateCommand::execute(
+            api_client,
+            None,
+            "Development".to_string(),
+            "toggl-cli".to_string(),
+        )
+        .await;
+
+        assert!(result.is_ok());
+    }
+}
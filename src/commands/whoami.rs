@@ -0,0 +1,128 @@
+use crate::api;
+use crate::models;
+use crate::output;
+use crate::profile;
+use api::client::ApiClient;
+use colored::Colorize;
+use models::ResultWithDefaultError;
+use output::Tabular;
+use serde::Serialize;
+
+pub struct WhoamiCommand;
+
+/// The account behind the active API token. The token itself is left out, so
+/// that it doesn't end up in terminal scrollback or logs.
+#[derive(Serialize, Debug, PartialEq)]
+struct Account {
+    profile: String,
+    email: String,
+    fullname: Option<String>,
+    timezone: String,
+    default_workspace_id: i64,
+    default_workspace: Option<String>,
+}
+
+impl std::fmt::Display for Account {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let summary = format!(
+            "{}: {}\n{}: {}\n{}: {}\n{}: {}\n{}: {}",
+            "Email".green(),
+            self.email,
+            "Name".green(),
+            self.fullname
+                .as_ref()
+                .unwrap_or(&"none".yellow().to_string()),
+            "Timezone".green(),
+            self.timezone,
+            "Default workspace".green(),
+            match &self.default_workspace {
+                Some(name) => format!("{} ({})", name, self.default_workspace_id),
+                None => self.default_workspace_id.to_string(),
+            },
+            "Profile".green(),
+            self.profile,
+        );
+        write!(f, "{}", summary)
+    }
+}
+
+impl Tabular for Account {
+    fn headers() -> Vec<&'static str> {
+        vec![
+            "profile",
+            "email",
+            "fullname",
+            "timezone",
+            "default_workspace_id",
+            "default_workspace",
+        ]
+    }
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.profile.clone(),
+            self.email.clone(),
+            self.fullname.clone().unwrap_or_default(),
+            self.timezone.clone(),
+            self.default_workspace_id.to_string(),
+            self.default_workspace.clone().unwrap_or_default(),
+        ]
+    }
+}
+
+async fn get_account(api_client: &impl ApiClient) -> ResultWithDefaultError<Account> {
+    let user = api_client.get_user().await?;
+    let workspaces = api_client.get_workspaces().await?;
+    Ok(Account {
+        profile: profile::get_profile().to_string(),
+        default_workspace: workspaces
+            .get(&user.default_workspace_id)
+            .map(|w| w.name.clone()),
+        email: user.email,
+        fullname: user.fullname,
+        timezone: user.timezone,
+        default_workspace_id: user.default_workspace_id,
+    })
+}
+
+impl WhoamiCommand {
+    pub async fn execute(api_client: impl ApiClient) -> ResultWithDefaultError<()> {
+        let account = get_account(&api_client).await?;
+        output::print_item(Some(&account));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::client::MockApiClient;
+    use crate::models::{User, Workspace};
+
+    #[tokio::test]
+    async fn the_default_workspace_is_shown_by_name() {
+        let mut api_client = MockApiClient::new();
+        api_client.expect_get_user().returning(|| {
+            Ok(User {
+                api_token: "token".to_string(),
+                email: "toggl@user.org".to_string(),
+                fullname: Some("Toggl User".to_string()),
+                timezone: "Europe/Berlin".to_string(),
+                default_workspace_id: 1,
+            })
+        });
+        api_client.expect_get_workspaces().returning(|| {
+            let workspace = Workspace {
+                id: 1,
+                name: "Personal".to_string(),
+            };
+            Ok([(workspace.id, workspace)].into())
+        });
+
+        let account = get_account(&api_client).await.unwrap();
+
+        assert_eq!(account.email, "toggl@user.org");
+        assert_eq!(account.default_workspace, Some("Personal".to_string()));
+    }
+}
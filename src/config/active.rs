@@ -1,4 +1,5 @@
 use crate::models::ResultWithDefaultError;
+use crate::output;
 
 pub struct ConfigActiveCommand;
 
@@ -6,7 +7,7 @@ impl ConfigActiveCommand {
     pub async fn execute() -> ResultWithDefaultError<()> {
         let config_path = super::locate::locate_config_path()?;
         let track_config = super::parser::get_config_from_file(config_path)?;
-        println!("{}", track_config.get_active_config()?);
+        output::print_item(Some(track_config.get_active_config()?));
         Ok(())
     }
 }
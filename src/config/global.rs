@@ -0,0 +1,59 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::models::ResultWithDefaultError;
+
+const GLOBAL_CONFIG_FILENAME: &str = "config.toml";
+
+/// GlobalConfig holds settings that apply to every invocation, regardless of
+/// the directory the CLI is run from. It lives in the config root next to the
+/// per-directory configs and every field is optional.
+///
+/// ```toml
+/// # Base URL of the v9 API, e.g. to talk to a local mock server
+/// api_url = "http://localhost:8080/api/v9"
+///
+/// # Seconds projects, tasks and clients are cached on disk, 0 disables the cache
+/// cache_ttl = 3600
+///
+/// # Command printing the API token, e.g. of a password manager. It's run with
+/// # TOGGL_PROFILE set, so that it can print the token of the active profile.
+/// token_command = "pass show toggl/$TOGGL_PROFILE"
+///
+/// # Rate limited (429) requests, and failed (5xx) ones that are safe to repeat
+/// [retry]
+/// max_attempts = 3 # including the first request
+/// timeout = 30     # seconds spent retrying before giving up
+/// ```
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct GlobalConfig {
+    pub api_url: Option<String>,
+    pub cache_ttl: Option<u64>,
+    pub token_command: Option<String>,
+    #[serde(default)]
+    pub retry: RetryConfig,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct RetryConfig {
+    pub max_attempts: Option<u32>,
+    pub timeout: Option<u64>,
+}
+
+pub fn get_global_config_path() -> PathBuf {
+    super::locate::get_config_root().join(GLOBAL_CONFIG_FILENAME)
+}
+
+pub fn get_global_config() -> ResultWithDefaultError<GlobalConfig> {
+    let path = get_global_config_path();
+    if !path.exists() {
+        return Ok(GlobalConfig::default());
+    }
+    let contents = std::fs::read_to_string(path)?;
+    let config: GlobalConfig = toml::from_str(&contents)?;
+
+    Ok(config)
+}
@@ -4,6 +4,11 @@ use base64::{engine::general_purpose, Engine as _};
 use lazy_static::lazy_static;
 
 use crate::error::ConfigError;
+use crate::profile;
+
+const CACHE_FILENAME: &str = "cache.json";
+const JOURNAL_FILENAME: &str = "journal.json";
+const CREDENTIALS_FILENAME: &str = "credentials";
 
 lazy_static! {
     pub static ref TRACKED_PATH: Option<PathBuf> = locate_tracked_path().ok();
@@ -45,13 +50,35 @@ pub fn get_config_path_for_current_dir() -> Result<PathBuf, Box<dyn std::error::
     Ok(get_encoded_config_path(&config_root, &path))
 }
 
-fn get_config_root() -> PathBuf {
+pub fn get_config_root() -> PathBuf {
     directories::ProjectDirs::from("studio.watercooler", "labs", "toggl-cli")
         .unwrap()
         .config_local_dir()
         .to_path_buf()
 }
 
+pub fn get_cache_path() -> PathBuf {
+    get_config_root().join(get_profile_filename(CACHE_FILENAME))
+}
+
+pub fn get_journal_path() -> PathBuf {
+    get_config_root().join(get_profile_filename(JOURNAL_FILENAME))
+}
+
+pub fn get_credentials_path() -> PathBuf {
+    get_config_root().join(get_profile_filename(CREDENTIALS_FILENAME))
+}
+
+/// Files of the default profile keep their original names, the ones of other
+/// profiles get the profile name as a prefix, e.g. `work.cache.json`.
+fn get_profile_filename(filename: &str) -> String {
+    if profile::is_default_profile() {
+        filename.to_string()
+    } else {
+        format!("{}.{}", profile::get_profile(), filename)
+    }
+}
+
 fn get_encoded_config_path(config_root: &Path, path: &Path) -> PathBuf {
     let encoded = general_purpose::STANDARD.encode(
         path.to_str()
@@ -1,4 +1,5 @@
 pub mod active;
+pub mod global;
 pub mod init;
 pub mod locate;
 pub mod manage;
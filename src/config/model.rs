@@ -6,11 +6,15 @@ use serde::de::{self, Deserializer, MapAccess, Visitor};
 use serde::{Deserialize, Serialize};
 
 use crate::error::ConfigError;
-use crate::models::{Entities, ResultWithDefaultError, TimeEntry};
+use crate::models::{Project, ResultWithDefaultError, Task, TimeEntry};
 use crate::utilities;
+use std::collections::HashMap;
 
 /// BranchConfig optionally determines workspace, description, project, task,
-/// tags, and billable status of a time entry.
+/// tags, and billable status of a time entry. With `create_project` set, a
+/// project that doesn't exist yet is created, under `client` if one is given.
+/// `profile` pins the profile whose credentials are used, unless another one
+/// is given on the command line.
 /// The fields are optional, and if not specified, the default values will be
 /// used. The string fields support templating, which will be replaced with live
 /// values on deserialization.
@@ -37,6 +41,8 @@ use crate::utilities;
 /// workspace = "Default"
 /// description = "Working on {{branch}} for {{base_dir}}"
 /// project = "Default"
+/// client = "Acme"
+/// create_project = true
 /// task = "Development"
 /// tags = ["{{branch}}", "{{$ date +\"%Y\"}}"]
 /// billable = true
@@ -60,6 +66,8 @@ use crate::utilities;
 ///  "workspace": "Default",
 ///  "description": "Working on my-feature for project",
 ///  "project": "Default",
+///  "client": "Acme",
+///  "create_project": true,
 ///  "task": "Development",
 ///  "tags": ["my-feature", "2023"],
 ///  "billable": true
@@ -67,9 +75,12 @@ use crate::utilities;
 /// ```
 #[derive(Debug, Serialize, Clone, Default)]
 pub struct BranchConfig {
+    pub profile: Option<String>,
     pub workspace: Option<String>,
     pub description: Option<String>,
     pub project: Option<String>,
+    pub client: Option<String>,
+    pub create_project: bool,
     pub task: Option<String>,
     pub tags: Option<Vec<String>>,
     pub billable: bool,
@@ -81,14 +92,27 @@ pub struct TrackConfig {
     pub configs: Vec<(String, BranchConfig)>,
 }
 
+const PROFILE: &str = "profile";
 const WORKSPACE: &str = "workspace";
 const DESCRIPTION: &str = "description";
 const PROJECT: &str = "project";
+const CLIENT: &str = "client";
+const CREATE_PROJECT: &str = "create_project";
 const TASK: &str = "task";
 const TAGS: &str = "tags";
 const BILLABLE: &str = "billable";
 
-const FIELDS: &[&str] = &[WORKSPACE, DESCRIPTION, PROJECT, TASK, TAGS, BILLABLE];
+const FIELDS: &[&str] = &[
+    PROFILE,
+    WORKSPACE,
+    DESCRIPTION,
+    PROJECT,
+    CLIENT,
+    CREATE_PROJECT,
+    TASK,
+    TAGS,
+    BILLABLE,
+];
 
 impl<'de> Deserialize<'de> for BranchConfig {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -112,14 +136,20 @@ impl<'de> Deserialize<'de> for BranchConfig {
             {
                 let process_template = |value: String| process_config_value(&self.base_dir, value);
 
+                let mut profile: Option<String> = None;
                 let mut workspace: Option<String> = None;
                 let mut description: Option<String> = None;
                 let mut project: Option<String> = None;
+                let mut client: Option<String> = None;
+                let mut create_project: Option<bool> = None;
                 let mut task: Option<String> = None;
                 let mut tags: Option<Vec<String>> = None;
                 let mut billable: Option<bool> = None;
                 while let Some(key) = map.next_key::<String>()? {
                     match key.as_str() {
+                        PROFILE => {
+                            profile = map.next_value().map(process_template)?;
+                        }
                         WORKSPACE => {
                             workspace = map.next_value().map(process_template)?;
                         }
@@ -129,6 +159,12 @@ impl<'de> Deserialize<'de> for BranchConfig {
                         PROJECT => {
                             project = map.next_value().map(process_template)?;
                         }
+                        CLIENT => {
+                            client = map.next_value().map(process_template)?;
+                        }
+                        CREATE_PROJECT => {
+                            create_project = Some(map.next_value()?);
+                        }
                         TASK => {
                             task = map.next_value().map(process_template)?;
                         }
@@ -146,9 +182,12 @@ impl<'de> Deserialize<'de> for BranchConfig {
                     }
                 }
                 Ok(BranchConfig {
+                    profile,
                     workspace,
                     description,
                     project,
+                    client,
+                    create_project: create_project.unwrap_or(false),
                     task,
                     tags,
                     billable: billable.unwrap_or(false),
@@ -171,7 +210,11 @@ impl<'de> Deserialize<'de> for BranchConfig {
 impl std::fmt::Display for BranchConfig {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let summary = format!(
-            "{}: {}\n{}: {}\n{}: {}\n{}: {}\n{}: {}\n{}: {}\n",
+            "{}: {}\n{}: {}\n{}: {}\n{}: {}\n{}: {}\n{}: {}\n{}: {}\n{}: {}\n{}: {}\n",
+            PROFILE.green(),
+            self.profile
+                .as_ref()
+                .unwrap_or(&"default".purple().to_string()),
             WORKSPACE.green(),
             self.workspace
                 .as_ref()
@@ -184,6 +227,10 @@ impl std::fmt::Display for BranchConfig {
             self.project
                 .as_ref()
                 .unwrap_or(&"none".yellow().to_string()),
+            CLIENT.green(),
+            self.client.as_ref().unwrap_or(&"none".yellow().to_string()),
+            CREATE_PROJECT.green(),
+            self.create_project,
             TASK.green(),
             self.task
                 .as_ref()
@@ -475,7 +522,7 @@ fn process_config_value(base_dir: &Path, input: String) -> Option<String> {
                 token.push(c);
             }
             let resolved = resolve_token(base_dir, &token).map_err(|e| {
-                println!("Failed to resolve token: {}", e);
+                eprintln!("Failed to resolve token: {}", e);
             });
             if let Ok(resolved_token) = resolved {
                 result.push_str(&resolved_token);
@@ -513,29 +560,37 @@ impl TrackConfig {
         let current_dir = std::env::current_dir()?;
         return Ok(self.get_branch_config_for_dir(&current_dir));
     }
-    pub fn get_default_entry(&self, entities: Entities) -> ResultWithDefaultError<TimeEntry> {
+    /// The time entry described by the active config, within the given workspace.
+    /// Without one, e.g. when it couldn't be fetched offline, projects of any
+    /// workspace are matched.
+    pub fn get_default_entry(
+        &self,
+        workspace_id: Option<i64>,
+        projects: &HashMap<i64, Project>,
+        tasks: &HashMap<i64, Task>,
+    ) -> ResultWithDefaultError<TimeEntry> {
         let config = self.get_active_config()?;
 
         let project = config.project.clone().and_then(|name| {
-            entities
-                .projects
-                .clone()
-                .into_values()
-                .find(|p| p.name == name)
+            projects
+                .values()
+                .find(|p| p.name == name && workspace_id.map_or(true, |id| p.workspace_id == id))
+                .cloned()
         });
 
         let project_id = project.clone().map(|p| p.id);
 
         let task = config.task.clone().and_then(|name| {
-            entities
-                .tasks
-                .clone()
-                .into_values()
-                .find(|t| t.name == name && t.project.id == project_id.unwrap())
+            tasks
+                .values()
+                .find(|t| t.name == name && Some(t.project.id) == project_id)
+                .cloned()
         });
 
         let time_entry = TimeEntry {
-            // TODO: Add support for workspace
+            workspace_id: workspace_id
+                .or(project.as_ref().map(|p| p.workspace_id))
+                .unwrap_or(TimeEntry::default().workspace_id),
             description: config.description.clone().unwrap_or_default(),
             billable: config.billable,
             tags: config.tags.clone().unwrap_or_default(),
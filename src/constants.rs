@@ -1,22 +1,65 @@
 pub const OUTDATED_APP_ERROR_MESSAGE: &str =
     "Make sure you are on the latest version of the app or file an issue here:";
+pub const DEFAULT_API_URL: &str = "https://track.toggl.com/api/v9";
+pub const DEFAULT_PROFILE: &str = "default";
 pub const CLIENT_NAME: &str = "github.com/watercooler-labs/toggl-cli/toggl-cli";
 pub const GENERIC_ERROR: &str = "Something went wrong.";
 pub const NETWORK_ERROR_MESSAGE: &str =
     "An error occurred when making a network request\nCheck your connection and try again.";
 pub const DESERIALIZATION_ERROR_MESSAGE: &str = "An error occurred when making a network request.";
+pub const UNAUTHORIZED_ERROR_MESSAGE: &str = "Your API token was rejected by Toggl.";
+pub const LOGIN_REJECTED_ERROR_MESSAGE: &str = "Your email or password was rejected by Toggl.";
+pub const FORBIDDEN_ERROR_MESSAGE: &str = "You don't have access to this resource.";
+pub const NOT_FOUND_ERROR_MESSAGE: &str = "The requested resource could not be found.";
+pub const RATE_LIMITED_ERROR_MESSAGE: &str =
+    "Too many requests were sent to Toggl, wait a moment and try again.";
+pub const SERVER_ERROR_MESSAGE: &str = "Toggl is having trouble handling the request";
+pub const REQUEST_ERROR_MESSAGE: &str = "Toggl rejected the request";
 pub const ISSUE_LINK: &str = "https://github.com/watercooler-labs/toggl-cli/issues/new";
 pub const CREDENTIALS_ACCESS_ERROR: &str = "An error occurred when reading your credentials.";
+pub const CREDENTIALS_KEYRING_DELETE_ERROR: &str =
+    "Your API token couldn't be deleted from the keyring, it might be locked or unavailable.";
+pub const CREDENTIALS_MISSING_ERROR: &str = "No API token was found.";
+pub const CREDENTIALS_READ_ONLY_ERROR: &str = "This credentials storage can't store API tokens.";
+pub const CREDENTIALS_PERMISSIONS_ERROR: &str =
+    "The token file must only be readable by you, but other users can access";
+pub const CREDENTIALS_COMMAND_ERROR: &str = "The token_command failed to print your API token.";
 pub const FZF_NOT_INSTALLED_ERROR: &str = "fzf could not be found. Is it installed?";
 pub const OPERATION_CANCELLED: &str = "Operation cancelled";
 pub const CONFIG_FILE_NOT_FOUND_ERROR: &str = "No config file found";
 pub const CONFIG_PARSE_ERROR: &str = "Failed to parse config file";
 pub const CONFIG_UNRECOGNIZED_MACRO_ERROR: &str = "Unrecognized macro in config file";
 pub const CONFIG_SHELL_MACRO_RESOLUTION_ERROR: &str = "Failed to resolve shell macro";
+pub const EXIT_CODE_GENERIC: i32 = 1;
+pub const EXIT_CODE_ARGUMENT: i32 = 2;
+pub const EXIT_CODE_AUTHENTICATION: i32 = 3;
+pub const EXIT_CODE_NETWORK: i32 = 4;
+pub const EXIT_CODE_NOT_FOUND: i32 = 5;
+pub const EXIT_CODE_CONFIG: i32 = 6;
+pub const EXIT_CODE_CANCELLED: i32 = 130;
 pub const NO_PROJECT: &str = "No Project";
 pub const NO_DESCRIPTION: &str = "(no description)";
+pub const NO_CLIENT: &str = "No Client";
+pub const NO_TAGS: &str = "No Tags";
 pub const DIRECTORY_NOT_FOUND_ERROR: &str = "Directory not found";
 pub const NOT_A_DIRECTORY_ERROR: &str = "Not a directory";
+pub const WORKSPACE_NOT_FOUND_ERROR: &str = "No workspace found with the name";
+pub const CLIENT_NOT_FOUND_ERROR: &str = "No client found with the name";
+pub const PROJECT_NOT_FOUND_ERROR: &str = "No project found with the name";
+pub const TASK_NOT_FOUND_ERROR: &str = "No task found in the project with the name";
+pub const TAG_NOT_FOUND_ERROR: &str = "No tag found with the name";
+pub const STOP_BEFORE_START_ERROR: &str = "A time entry can't stop before it starts";
+pub const TIME_OUT_OF_RANGE_ERROR: &str = "The resulting time is out of range";
+pub const INVALID_DURATION_ERROR: &str = "Not a valid duration";
+pub const DURATION_EXAMPLES: &str = "Durations look like 1h30m, 90m, 1.5h or 45s";
+pub const INVALID_RANGE_ERROR: &str =
+    "Not a valid START..END range, END is excluded, e.g. monday..tomorrow";
+pub const INVALID_TIMEZONE_ERROR: &str = "Not a valid IANA timezone name";
+pub const INVALID_PROFILE_ERROR: &str =
+    "Profile names may only contain letters, digits, dashes and underscores";
+pub const INVALID_TIME_ERROR: &str = "Not a valid time";
+pub const TIME_EXAMPLES: &str =
+    "Times look like 09:00, yesterday 14:00, last monday, 2023-01-31 09:00 or now";
 
 #[cfg(target_os = "macos")]
 pub const SIMPLE_HOME_PATH: &str = "~/Library/Application Support";
@@ -1,21 +1,47 @@
 use crate::error;
 use crate::models;
+use crate::profile;
+use crate::utilities;
 use async_trait::async_trait;
 use error::StorageError;
 use keyring::Entry;
 #[cfg(test)]
 use mockall::automock;
 use models::ResultWithDefaultError;
+use std::path::PathBuf;
+use std::process::Stdio;
+
+const API_TOKEN_ENV_VAR: &str = "TOGGL_API_TOKEN";
+const PROFILE_ENV_VAR: &str = "TOGGL_PROFILE";
 
 pub struct Credentials {
     pub api_token: String,
 }
 
+/// A place API tokens are read from and stored in. `read` fails with
+/// `StorageError::Missing` when the storage holds no token, so that the next
+/// one can be tried, and `persist` and `delete` with `StorageError::ReadOnly`
+/// when it can't store tokens at all.
 #[cfg_attr(test, automock)]
 #[async_trait]
 pub trait CredentialsStorage {
     fn read(&self) -> ResultWithDefaultError<Credentials>;
     fn persist(&self, api_token: String) -> ResultWithDefaultError<()>;
+    fn delete(&self) -> ResultWithDefaultError<()>;
+}
+
+pub fn is_missing(error: &(dyn std::error::Error + 'static)) -> bool {
+    matches!(
+        error.downcast_ref::<StorageError>(),
+        Some(StorageError::Missing)
+    )
+}
+
+fn is_read_only(error: &(dyn std::error::Error + 'static)) -> bool {
+    matches!(
+        error.downcast_ref::<StorageError>(),
+        Some(StorageError::ReadOnly)
+    )
 }
 
 pub struct KeyringStorage {
@@ -30,8 +56,17 @@ impl KeyringStorage {
 
 impl CredentialsStorage for KeyringStorage {
     fn read(&self) -> ResultWithDefaultError<Credentials> {
-        let api_token = self.keyring.get_password()?;
-        Ok(Credentials { api_token })
+        match self.keyring.get_password() {
+            Ok(api_token) => Ok(Credentials { api_token }),
+            // Headless machines often have no keyring at all, which is no
+            // different from one without a token for other storages.
+            Err(
+                keyring::Error::NoEntry
+                | keyring::Error::NoStorageAccess(_)
+                | keyring::Error::PlatformFailure(_),
+            ) => Err(Box::new(StorageError::Missing)),
+            Err(err) => Err(Box::new(err)),
+        }
     }
 
     fn persist(&self, api_token: String) -> ResultWithDefaultError<()> {
@@ -40,4 +75,331 @@ impl CredentialsStorage for KeyringStorage {
             Ok(_) => Ok(()),
         }
     }
+
+    fn delete(&self) -> ResultWithDefaultError<()> {
+        // Unlike reading, a keyring that can't be accessed might still hold the
+        // token, so only a missing entry means there's nothing to delete.
+        match self.keyring.delete_password() {
+            Ok(()) => Ok(()),
+            Err(keyring::Error::NoEntry) => Err(Box::new(StorageError::Missing)),
+            Err(_) => Err(Box::new(StorageError::KeyringDelete)),
+        }
+    }
+}
+
+/// Reads the API token from the TOGGL_API_TOKEN environment variable.
+pub struct EnvironmentStorage;
+
+impl CredentialsStorage for EnvironmentStorage {
+    fn read(&self) -> ResultWithDefaultError<Credentials> {
+        match std::env::var(API_TOKEN_ENV_VAR) {
+            Ok(api_token) if !api_token.trim().is_empty() => Ok(Credentials {
+                api_token: api_token.trim().to_string(),
+            }),
+            _ => Err(Box::new(StorageError::Missing)),
+        }
+    }
+
+    fn persist(&self, _api_token: String) -> ResultWithDefaultError<()> {
+        Err(Box::new(StorageError::ReadOnly))
+    }
+
+    fn delete(&self) -> ResultWithDefaultError<()> {
+        Err(Box::new(StorageError::ReadOnly))
+    }
+}
+
+/// Reads the API token from the output of the `token_command` of the global
+/// config, e.g. one of a password manager.
+pub struct CommandStorage {
+    command: Option<String>,
+}
+
+impl CommandStorage {
+    pub fn new(command: Option<String>) -> CommandStorage {
+        Self { command }
+    }
+}
+
+impl CredentialsStorage for CommandStorage {
+    fn read(&self) -> ResultWithDefaultError<Credentials> {
+        let command = match &self.command {
+            Some(command) => command,
+            None => return Err(Box::new(StorageError::Missing)),
+        };
+        // Password managers might ask for a passphrase, so only stdout is captured.
+        let output = utilities::get_shell_cmd(command)
+            .env(PROFILE_ENV_VAR, profile::get_profile())
+            .stdin(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .output()
+            .map_err(|err| StorageError::CommandFailed(format!("{}: {}", command, err)))?;
+        if !output.status.success() {
+            return Err(Box::new(StorageError::CommandFailed(format!(
+                "{} exited with {}",
+                command, output.status
+            ))));
+        }
+        let api_token = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if api_token.is_empty() {
+            return Err(Box::new(StorageError::CommandFailed(format!(
+                "{} printed nothing",
+                command
+            ))));
+        }
+
+        Ok(Credentials { api_token })
+    }
+
+    fn persist(&self, _api_token: String) -> ResultWithDefaultError<()> {
+        Err(Box::new(StorageError::ReadOnly))
+    }
+
+    fn delete(&self) -> ResultWithDefaultError<()> {
+        Err(Box::new(StorageError::ReadOnly))
+    }
+}
+
+/// Stores the API token in a plain text file, which must only be accessible
+/// by its owner.
+pub struct FileStorage {
+    path: PathBuf,
+}
+
+impl FileStorage {
+    pub fn new(path: PathBuf) -> FileStorage {
+        Self { path }
+    }
+
+    #[cfg(unix)]
+    fn check_permissions(&self) -> ResultWithDefaultError<()> {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&self.path)?.permissions().mode();
+        if mode & 0o077 != 0 {
+            return Err(Box::new(StorageError::InsecurePermissions(
+                self.path.clone(),
+            )));
+        }
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn check_permissions(&self) -> ResultWithDefaultError<()> {
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    fn write(&self, api_token: &str) -> std::io::Result<()> {
+        use std::io::Write;
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(&self.path)?;
+        // The mode only applies to new files.
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        file.write_all(api_token.as_bytes())
+    }
+
+    #[cfg(not(unix))]
+    fn write(&self, api_token: &str) -> std::io::Result<()> {
+        std::fs::write(&self.path, api_token)
+    }
+}
+
+impl CredentialsStorage for FileStorage {
+    fn read(&self) -> ResultWithDefaultError<Credentials> {
+        if !self.path.exists() {
+            return Err(Box::new(StorageError::Missing));
+        }
+        self.check_permissions()?;
+        let api_token = std::fs::read_to_string(&self.path)?.trim().to_string();
+        if api_token.is_empty() {
+            return Err(Box::new(StorageError::Missing));
+        }
+
+        Ok(Credentials { api_token })
+    }
+
+    fn persist(&self, api_token: String) -> ResultWithDefaultError<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent).map_err(|_| StorageError::Write)?;
+        }
+        self.write(&api_token).map_err(|_| StorageError::Write)?;
+        Ok(())
+    }
+
+    fn delete(&self) -> ResultWithDefaultError<()> {
+        if !self.path.exists() {
+            return Err(Box::new(StorageError::Missing));
+        }
+        std::fs::remove_file(&self.path).map_err(|_| StorageError::Write)?;
+        Ok(())
+    }
+}
+
+/// Tries several storages in order. The token is read from the first one that
+/// has it, persisted in the first one that can store it and deleted from all
+/// of them.
+pub struct ChainedStorage {
+    storages: Vec<Box<dyn CredentialsStorage>>,
+}
+
+impl ChainedStorage {
+    pub fn new(storages: Vec<Box<dyn CredentialsStorage>>) -> ChainedStorage {
+        Self { storages }
+    }
+}
+
+impl CredentialsStorage for ChainedStorage {
+    fn read(&self) -> ResultWithDefaultError<Credentials> {
+        for storage in &self.storages {
+            match storage.read() {
+                Err(err) if is_missing(err.as_ref()) => continue,
+                result => return result,
+            }
+        }
+        Err(Box::new(StorageError::Missing))
+    }
+
+    fn persist(&self, api_token: String) -> ResultWithDefaultError<()> {
+        let mut last_error: Box<dyn std::error::Error> = Box::new(StorageError::ReadOnly);
+        for storage in &self.storages {
+            match storage.persist(api_token.clone()) {
+                Ok(()) => return Ok(()),
+                Err(err) if is_read_only(err.as_ref()) => continue,
+                Err(err) => last_error = err,
+            }
+        }
+        Err(last_error)
+    }
+
+    fn delete(&self) -> ResultWithDefaultError<()> {
+        // A storage that fails doesn't keep the token from being deleted from
+        // the others, but its error is still returned.
+        let mut deleted = false;
+        let mut error = None;
+        for storage in &self.storages {
+            match storage.delete() {
+                Ok(()) => deleted = true,
+                Err(err) if is_missing(err.as_ref()) || is_read_only(err.as_ref()) => continue,
+                Err(err) => error = error.or(Some(err)),
+            }
+        }
+        match error {
+            Some(err) => Err(err),
+            None if deleted => Ok(()),
+            None => Err(Box::new(StorageError::Missing)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn storage_with_token(api_token: &'static str) -> Box<dyn CredentialsStorage> {
+        let mut storage = MockCredentialsStorage::new();
+        storage.expect_read().returning(move || {
+            Ok(Credentials {
+                api_token: api_token.to_string(),
+            })
+        });
+        Box::new(storage)
+    }
+
+    fn storage_failing_with(error: fn() -> StorageError) -> MockCredentialsStorage {
+        let mut storage = MockCredentialsStorage::new();
+        storage
+            .expect_read()
+            .returning(move || Err(Box::new(error())));
+        storage
+            .expect_persist()
+            .returning(move |_| Err(Box::new(error())));
+        storage
+            .expect_delete()
+            .returning(move || Err(Box::new(error())));
+        storage
+    }
+
+    #[test]
+    fn the_token_is_read_from_the_first_storage_that_has_one() {
+        let storage = ChainedStorage::new(vec![
+            Box::new(storage_failing_with(|| StorageError::Missing)),
+            storage_with_token("first"),
+            storage_with_token("second"),
+        ]);
+
+        assert_eq!(storage.read().unwrap().api_token, "first");
+    }
+
+    #[test]
+    fn the_token_is_persisted_in_the_first_storage_that_can_store_it() {
+        let mut file = MockCredentialsStorage::new();
+        file.expect_persist()
+            .withf(|api_token| api_token == "token")
+            .times(1)
+            .returning(|_| Ok(()));
+        let storage = ChainedStorage::new(vec![
+            Box::new(storage_failing_with(|| StorageError::ReadOnly)),
+            Box::new(storage_failing_with(|| StorageError::Write)),
+            Box::new(file),
+        ]);
+
+        assert!(storage.persist("token".to_string()).is_ok());
+    }
+
+    #[test]
+    fn the_token_is_deleted_from_every_storage_that_has_one() {
+        let mut keyring = MockCredentialsStorage::new();
+        keyring.expect_delete().times(1).returning(|| Ok(()));
+        let mut file = MockCredentialsStorage::new();
+        file.expect_delete().times(1).returning(|| Ok(()));
+        let storage = ChainedStorage::new(vec![
+            Box::new(storage_failing_with(|| StorageError::ReadOnly)),
+            Box::new(keyring),
+            Box::new(storage_failing_with(|| StorageError::Missing)),
+            Box::new(file),
+        ]);
+
+        assert!(storage.delete().is_ok());
+    }
+
+    #[test]
+    fn failing_storages_dont_keep_the_token_from_being_deleted_from_others() {
+        let mut file = MockCredentialsStorage::new();
+        file.expect_delete().times(1).returning(|| Ok(()));
+        let storage = ChainedStorage::new(vec![
+            Box::new(storage_failing_with(|| StorageError::Write)),
+            Box::new(file),
+        ]);
+
+        let error = storage.delete().err().unwrap();
+
+        assert!(matches!(
+            error.downcast_ref::<StorageError>(),
+            Some(StorageError::Write)
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn token_files_other_users_can_read_are_rejected() {
+        use std::os::unix::fs::PermissionsExt;
+        let path = std::env::temp_dir().join(format!("toggl-credentials-{}", std::process::id()));
+        let storage = FileStorage::new(path.clone());
+        storage.persist("token".to_string()).unwrap();
+        assert_eq!(storage.read().unwrap().api_token, "token");
+
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        let error = storage.read().err().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(
+            error.downcast_ref::<StorageError>(),
+            Some(StorageError::InsecurePermissions(_))
+        ));
+    }
 }
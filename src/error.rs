@@ -1,4 +1,5 @@
 use crate::constants;
+use crate::profile;
 use colored::Colorize;
 use std::error::Error;
 use std::fmt::Display;
@@ -8,6 +9,14 @@ use std::path::PathBuf;
 pub enum ApiError {
     Network,
     Deserialization,
+    Unauthorized(String),
+    /// The email or password given to `toggl auth --email` was rejected.
+    LoginRejected(String),
+    Forbidden(String),
+    NotFound(String),
+    RateLimited(String),
+    Server(u16, String),
+    Request(u16, String),
 }
 
 impl Display for ApiError {
@@ -20,26 +29,152 @@ impl Display for ApiError {
                 constants::OUTDATED_APP_ERROR_MESSAGE.blue().bold(),
                 constants::ISSUE_LINK.blue().bold().underline()
             ),
+            ApiError::Unauthorized(message) => format!(
+                "{}{}\n{} {}",
+                constants::UNAUTHORIZED_ERROR_MESSAGE.red(),
+                format_api_message(message),
+                "Run".blue(),
+                profile::get_auth_command().blue().bold(),
+            ),
+            ApiError::LoginRejected(message) => format!(
+                "{}{}\n{} {}",
+                constants::LOGIN_REJECTED_ERROR_MESSAGE.red(),
+                format_api_message(message),
+                "Check them and try again, or log in with your API token instead:".blue(),
+                profile::get_auth_command().blue().bold(),
+            ),
+            ApiError::Forbidden(message) => format!(
+                "{}{}",
+                constants::FORBIDDEN_ERROR_MESSAGE.red(),
+                format_api_message(message),
+            ),
+            ApiError::NotFound(message) => format!(
+                "{}{}",
+                constants::NOT_FOUND_ERROR_MESSAGE.red(),
+                format_api_message(message),
+            ),
+            ApiError::RateLimited(message) => format!(
+                "{}{}",
+                constants::RATE_LIMITED_ERROR_MESSAGE.red(),
+                format_api_message(message),
+            ),
+            ApiError::Server(status, message) => format!(
+                "{} ({}){}",
+                constants::SERVER_ERROR_MESSAGE.red(),
+                status,
+                format_api_message(message),
+            ),
+            ApiError::Request(status, message) => format!(
+                "{} ({}){}\n{} {}",
+                constants::REQUEST_ERROR_MESSAGE.red(),
+                status,
+                format_api_message(message),
+                constants::OUTDATED_APP_ERROR_MESSAGE.blue().bold(),
+                constants::ISSUE_LINK.blue().bold().underline()
+            ),
         };
         write!(f, "{}", summary)
     }
 }
 
+fn format_api_message(message: &str) -> String {
+    if message.is_empty() {
+        "".to_string()
+    } else {
+        format!("\n{}: {}", "Toggl says".yellow(), message.yellow().bold())
+    }
+}
+
 impl Error for ApiError {}
 
+pub fn is_network_error(error: &(dyn Error + 'static)) -> bool {
+    matches!(error.downcast_ref::<ApiError>(), Some(ApiError::Network))
+}
+
+/// Maps an error to the exit code of its category, so scripts can tell apart
+/// e.g. a missing API token from Toggl being unreachable.
+pub fn get_exit_code(error: &(dyn Error + 'static)) -> i32 {
+    if let Some(error) = error.downcast_ref::<ApiError>() {
+        return match error {
+            ApiError::Unauthorized(_) | ApiError::LoginRejected(_) | ApiError::Forbidden(_) => {
+                constants::EXIT_CODE_AUTHENTICATION
+            }
+            ApiError::Network | ApiError::RateLimited(_) | ApiError::Server(_, _) => {
+                constants::EXIT_CODE_NETWORK
+            }
+            ApiError::NotFound(_) => constants::EXIT_CODE_NOT_FOUND,
+            ApiError::Deserialization | ApiError::Request(_, _) => constants::EXIT_CODE_GENERIC,
+        };
+    }
+    if let Some(error) = error.downcast_ref::<ArgumentError>() {
+        return match error {
+            ArgumentError::WorkspaceNotFound(_)
+            | ArgumentError::ClientNotFound(_)
+            | ArgumentError::ProjectNotFound(_)
+            | ArgumentError::TaskNotFound(_) => constants::EXIT_CODE_NOT_FOUND,
+            _ => constants::EXIT_CODE_ARGUMENT,
+        };
+    }
+    // Arguments rejected while parsing, e.g. an unparseable time.
+    if error.is::<structopt::clap::Error>() {
+        return constants::EXIT_CODE_ARGUMENT;
+    }
+    if error.is::<StorageError>() || error.is::<keyring::Error>() {
+        return constants::EXIT_CODE_AUTHENTICATION;
+    }
+    if error.is::<ConfigError>() || error.is::<toml::de::Error>() {
+        return constants::EXIT_CODE_CONFIG;
+    }
+    match error.downcast_ref::<PickerError>() {
+        Some(PickerError::Cancelled) => constants::EXIT_CODE_CANCELLED,
+        _ => constants::EXIT_CODE_GENERIC,
+    }
+}
+
 #[derive(Debug)]
 pub enum StorageError {
     Write,
+    KeyringDelete,
+    Missing,
+    ReadOnly,
+    InsecurePermissions(PathBuf),
+    CommandFailed(String),
 }
 
 impl Display for StorageError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let summary = format!(
-            "{}\n{} {}",
-            constants::CREDENTIALS_ACCESS_ERROR.red(),
-            constants::OUTDATED_APP_ERROR_MESSAGE.blue().bold(),
-            constants::ISSUE_LINK.blue().bold().underline()
-        );
+        let summary = match self {
+            StorageError::Write => format!(
+                "{}\n{} {}",
+                constants::CREDENTIALS_ACCESS_ERROR.red(),
+                constants::OUTDATED_APP_ERROR_MESSAGE.blue().bold(),
+                constants::ISSUE_LINK.blue().bold().underline()
+            ),
+            StorageError::KeyringDelete => {
+                format!("{}", constants::CREDENTIALS_KEYRING_DELETE_ERROR.red())
+            }
+            StorageError::Missing => format!(
+                "{}\n{} {}\n{} {}",
+                constants::CREDENTIALS_MISSING_ERROR.red(),
+                "Set your API token first by running".blue(),
+                profile::get_auth_command().blue().bold(),
+                "You can find your API token at".blue(),
+                "https://track.toggl.com/profile".blue().bold().underline()
+            ),
+            StorageError::ReadOnly => format!("{}", constants::CREDENTIALS_READ_ONLY_ERROR.red()),
+            StorageError::InsecurePermissions(path) => format!(
+                "{} {}\n{} {}",
+                constants::CREDENTIALS_PERMISSIONS_ERROR.red(),
+                path.display(),
+                "Run".blue(),
+                format!("chmod 600 {}", path.display()).blue().bold()
+            ),
+            StorageError::CommandFailed(message) => format!(
+                "{}\n{}",
+                constants::CREDENTIALS_COMMAND_ERROR.red(),
+                message.trim_end()
+            ),
+        };
         write!(f, "{}", summary)
     }
 }
@@ -122,6 +257,18 @@ impl Error for ConfigError {}
 pub enum ArgumentError {
     DirectoryNotFound(PathBuf),
     NotADirectory(PathBuf),
+    WorkspaceNotFound(String),
+    ClientNotFound(String),
+    ProjectNotFound(String),
+    TaskNotFound(String),
+    TagNotFound(String),
+    StopBeforeStart,
+    TimeOutOfRange,
+    InvalidDuration(String),
+    InvalidTime(String),
+    InvalidTimezone(String),
+    InvalidRange(String),
+    InvalidProfile(String),
 }
 
 impl Display for ArgumentError {
@@ -141,9 +288,144 @@ impl Display for ArgumentError {
                     path.display()
                 )
             }
+            ArgumentError::WorkspaceNotFound(name) => {
+                format!(
+                    "{}: {}",
+                    constants::WORKSPACE_NOT_FOUND_ERROR.red(),
+                    name.bold()
+                )
+            }
+            ArgumentError::ClientNotFound(name) => {
+                format!(
+                    "{}: {}",
+                    constants::CLIENT_NOT_FOUND_ERROR.red(),
+                    name.bold()
+                )
+            }
+            ArgumentError::ProjectNotFound(name) => {
+                format!(
+                    "{}: {}",
+                    constants::PROJECT_NOT_FOUND_ERROR.red(),
+                    name.bold()
+                )
+            }
+            ArgumentError::TaskNotFound(name) => {
+                format!("{}: {}", constants::TASK_NOT_FOUND_ERROR.red(), name.bold())
+            }
+            ArgumentError::TagNotFound(name) => {
+                format!("{}: {}", constants::TAG_NOT_FOUND_ERROR.red(), name.bold())
+            }
+            ArgumentError::StopBeforeStart => {
+                format!("{}", constants::STOP_BEFORE_START_ERROR.red())
+            }
+            ArgumentError::TimeOutOfRange => {
+                format!("{}", constants::TIME_OUT_OF_RANGE_ERROR.red())
+            }
+            ArgumentError::InvalidDuration(value) => {
+                format!(
+                    "{}: {}\n{}",
+                    constants::INVALID_DURATION_ERROR.red(),
+                    value.bold(),
+                    constants::DURATION_EXAMPLES
+                )
+            }
+            ArgumentError::InvalidTime(value) => {
+                format!(
+                    "{}: {}\n{}",
+                    constants::INVALID_TIME_ERROR.red(),
+                    value.bold(),
+                    constants::TIME_EXAMPLES
+                )
+            }
+            ArgumentError::InvalidRange(value) => {
+                format!("{}: {}", constants::INVALID_RANGE_ERROR.red(), value.bold())
+            }
+            ArgumentError::InvalidProfile(name) => {
+                format!(
+                    "{}: {}",
+                    constants::INVALID_PROFILE_ERROR.red(),
+                    name.bold()
+                )
+            }
+            ArgumentError::InvalidTimezone(name) => {
+                format!(
+                    "{}: {}",
+                    constants::INVALID_TIMEZONE_ERROR.red(),
+                    name.bold()
+                )
+            }
         };
         writeln!(f, "{}", summary)
     }
 }
 
 impl Error for ArgumentError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arguments::CommandLineArguments;
+    use structopt::StructOpt;
+
+    fn exit_code(error: impl Error + 'static) -> i32 {
+        get_exit_code(&error)
+    }
+
+    #[test]
+    fn errors_map_to_the_exit_code_of_their_category() {
+        assert_eq!(
+            exit_code(ApiError::Unauthorized("".to_string())),
+            constants::EXIT_CODE_AUTHENTICATION
+        );
+        assert_eq!(
+            exit_code(ApiError::LoginRejected("".to_string())),
+            constants::EXIT_CODE_AUTHENTICATION
+        );
+        assert_eq!(
+            exit_code(keyring::Error::NoEntry),
+            constants::EXIT_CODE_AUTHENTICATION
+        );
+        assert_eq!(exit_code(ApiError::Network), constants::EXIT_CODE_NETWORK);
+        assert_eq!(
+            exit_code(ApiError::Server(502, "".to_string())),
+            constants::EXIT_CODE_NETWORK
+        );
+        assert_eq!(
+            exit_code(ApiError::NotFound("".to_string())),
+            constants::EXIT_CODE_NOT_FOUND
+        );
+        assert_eq!(
+            exit_code(ArgumentError::ProjectNotFound("".to_string())),
+            constants::EXIT_CODE_NOT_FOUND
+        );
+        assert_eq!(exit_code(ConfigError::Parse), constants::EXIT_CODE_CONFIG);
+        assert_eq!(
+            exit_code(PickerError::Cancelled),
+            constants::EXIT_CODE_CANCELLED
+        );
+        assert_eq!(
+            exit_code(ArgumentError::StopBeforeStart),
+            constants::EXIT_CODE_ARGUMENT
+        );
+        assert_eq!(
+            exit_code(PickerError::Generic),
+            constants::EXIT_CODE_GENERIC
+        );
+    }
+
+    #[test]
+    fn arguments_rejected_by_the_parser_map_to_the_argument_exit_code() {
+        let error = CommandLineArguments::from_iter_safe([
+            "toggl",
+            "add",
+            "--start",
+            "garbage",
+            "--duration",
+            "1h",
+        ])
+        .err()
+        .unwrap();
+
+        assert_eq!(exit_code(error), constants::EXIT_CODE_ARGUMENT);
+    }
+}
@@ -0,0 +1,71 @@
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::models::{ResultWithDefaultError, TimeEntry};
+
+/// An operation that couldn't reach the API and has to be replayed once the
+/// network is back. Each operation records the moment it happened, so the
+/// tracked time matches what the user did rather than when we synced.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "operation", rename_all = "snake_case")]
+pub enum JournalEntry {
+    Start {
+        time_entry: Box<TimeEntry>,
+        /// The workspace couldn't be fetched, so the entry is created in the
+        /// workspace of its project, or the user's default one, once it's synced.
+        #[serde(default)]
+        uses_default_workspace: bool,
+    },
+    Stop {
+        stop: DateTime<Utc>,
+    },
+}
+
+/// Journal keeps pending operations in the order they were recorded in a JSON
+/// file under the config root.
+pub struct Journal {
+    path: PathBuf,
+}
+
+impl Default for Journal {
+    fn default() -> Self {
+        Self::new(config::locate::get_journal_path())
+    }
+}
+
+impl Journal {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn read(&self) -> ResultWithDefaultError<Vec<JournalEntry>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let contents = std::fs::read_to_string(&self.path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    pub fn append(&self, entry: JournalEntry) -> ResultWithDefaultError<()> {
+        let mut entries = self.read()?;
+        entries.push(entry);
+        self.write(&entries)
+    }
+
+    pub fn write(&self, entries: &[JournalEntry]) -> ResultWithDefaultError<()> {
+        if entries.is_empty() {
+            if self.path.exists() {
+                std::fs::remove_file(&self.path)?;
+            }
+            return Ok(());
+        }
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(entries)?)?;
+        Ok(())
+    }
+}
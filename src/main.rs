@@ -5,53 +5,143 @@ mod config;
 mod constants;
 mod credentials;
 mod error;
+mod journal;
 mod models;
+mod output;
 mod picker;
+mod profile;
+mod time_parser;
+mod timezone;
 mod utilities;
 
+use api::cache::CachedApiClient;
 use api::client::ApiClient;
 use api::client::V9ApiClient;
+use api::memoized::MemoizedApiClient;
+use api::retry::RetryPolicy;
+use arguments::CacheSubCommand;
+use arguments::ClientsSubCommand;
+use arguments::Command;
+use arguments::Command::Add;
 use arguments::Command::Auth;
+use arguments::Command::Cache;
+use arguments::Command::Clients;
 use arguments::Command::Config;
 use arguments::Command::Continue;
 use arguments::Command::Current;
+use arguments::Command::Delete;
+use arguments::Command::Edit;
 use arguments::Command::List;
+use arguments::Command::Logout;
+use arguments::Command::Projects;
+use arguments::Command::Report;
 use arguments::Command::Running;
 use arguments::Command::Start;
 use arguments::Command::Stop;
+use arguments::Command::Sync;
+use arguments::Command::Tags;
+use arguments::Command::Tasks;
+use arguments::Command::Whoami;
 use arguments::CommandLineArguments;
 use arguments::ConfigSubCommand;
-use colored::Colorize;
+use arguments::ProjectsSubCommand;
+use arguments::StartArguments;
+use arguments::TagsSubCommand;
+use arguments::TasksSubCommand;
+use commands::add::AddCommand;
 use commands::auth::AuthenticationCommand;
+use commands::cache::CacheClearCommand;
+use commands::clients::{
+    ClientsArchiveCommand, ClientsCreateCommand, ClientsListCommand, ClientsRenameCommand,
+};
 use commands::cont::ContinueCommand;
+use commands::delete::DeleteCommand;
+use commands::edit::{EditCommand, EditTarget};
 use commands::list::ListCommand;
+use commands::logout::LogoutCommand;
+use commands::projects::{
+    ProjectsArchiveCommand, ProjectsCreateCommand, ProjectsListCommand, ProjectsRenameCommand,
+};
+use commands::report::ReportCommand;
 use commands::running::RunningTimeEntryCommand;
 use commands::start::StartCommand;
 use commands::stop::{StopCommand, StopCommandOrigin};
-use credentials::{Credentials, CredentialsStorage, KeyringStorage};
+use commands::sync::{SyncCommand, SyncCommandOrigin};
+use commands::tags::{TagsCreateCommand, TagsDeleteCommand, TagsListCommand, TagsRenameCommand};
+use commands::tasks::{TasksCreateCommand, TasksListCommand};
+use commands::whoami::WhoamiCommand;
+use credentials::{
+    ChainedStorage, CommandStorage, Credentials, CredentialsStorage, EnvironmentStorage,
+    FileStorage, KeyringStorage,
+};
+use journal::Journal;
 use keyring::Entry;
 use models::ResultWithDefaultError;
 use std::io;
+use std::time::Duration;
+use structopt::clap::ErrorKind;
 use structopt::StructOpt;
 
+const DEFAULT_CACHE_TTL_SECONDS: u64 = 60 * 60;
+
+struct ApiClientSettings {
+    proxy: Option<String>,
+    api_url: String,
+    retry_policy: RetryPolicy,
+    cache_ttl: chrono::Duration,
+    refresh_cache: bool,
+    token_command: Option<String>,
+}
+
 #[tokio::main]
-async fn main() -> ResultWithDefaultError<()> {
-    let parsed_args = CommandLineArguments::from_args();
-    match execute_subcommand(parsed_args).await {
-        Ok(()) => Ok(()),
+async fn main() {
+    let parsed_args = match CommandLineArguments::from_iter_safe(std::env::args_os()) {
+        Ok(args) => args,
+        // Help and version are printed to stdout and exit successfully.
+        Err(error)
+            if matches!(
+                error.kind,
+                ErrorKind::HelpDisplayed | ErrorKind::VersionDisplayed
+            ) =>
+        {
+            error.exit()
+        }
         Err(error) => {
-            // We are catching the error and pretty printing it instead of letting the
-            // program error. Since we are not meant to be used other programs, I think
-            // it's fine to always return a 0 error code, but we might wanna revisit this.
-            print!("{}", error);
-            Ok(())
+            eprintln!("{}", error.to_string().trim_end());
+            std::process::exit(error::get_exit_code(&error));
         }
+    };
+    if let Err(error) = execute_subcommand(parsed_args).await {
+        // We are catching the error and pretty printing it instead of letting the
+        // program print its Debug representation.
+        eprintln!("{}", error.to_string().trim_end());
+        std::process::exit(error::get_exit_code(error.as_ref()));
     }
 }
 
 async fn execute_subcommand(args: CommandLineArguments) -> ResultWithDefaultError<()> {
+    output::set_output_format(args.output);
     let command = args.cmd;
-    let get_default_api_client = || get_api_client(args.proxy.clone());
+    let global_config = config::global::get_global_config()?;
+    let settings = ApiClientSettings {
+        proxy: args.proxy,
+        api_url: args
+            .api_url
+            .or(global_config.api_url)
+            .unwrap_or_else(|| constants::DEFAULT_API_URL.to_string()),
+        retry_policy: RetryPolicy::new(
+            global_config.retry.max_attempts,
+            global_config.retry.timeout.map(Duration::from_secs),
+        ),
+        cache_ttl: chrono::Duration::seconds(
+            global_config
+                .cache_ttl
+                .unwrap_or(DEFAULT_CACHE_TTL_SECONDS)
+                .try_into()?,
+        ),
+        refresh_cache: args.refresh,
+        token_command: global_config.token_command,
+    };
     let picker = picker::get_picker(args.fzf);
     if let Some(directory) = args.directory {
         if !directory.exists() {
@@ -62,42 +152,136 @@ async fn execute_subcommand(args: CommandLineArguments) -> ResultWithDefaultErro
         }
         std::env::set_current_dir(directory)?;
     }
+    // The profile decides which credentials, cache and journal are used, so it
+    // has to be known before any of them.
+    profile::set_profile(profile::resolve_profile(args.profile)?);
+    let uses_api = !matches!(
+        command,
+        Some(Auth { .. } | Logout | Config { .. } | Cache { .. })
+    );
+    // The client is built once and shared, so that the credentials are only
+    // read once, e.g. a token_command only prompts once, and requests such as
+    // the user's profile are memoized across the steps below.
+    let mut api_client = if uses_api {
+        Some(get_api_client(&settings)?)
+    } else {
+        None
+    };
+    let journal = Journal::default();
+    let syncs_pending_operations =
+        uses_api && !matches!(command, Some(Sync)) && !journal.read()?.is_empty();
+    if let Some(timezone) = args.tz {
+        timezone::set_timezone(timezone);
+    }
+    if let Some(api_client) = &api_client {
+        if args.tz.is_none() && (syncs_pending_operations || uses_timezone(&command)) {
+            timezone::set_timezone(timezone::get_profile_timezone(api_client).await?);
+        }
+        if syncs_pending_operations {
+            SyncCommand::execute(api_client, &journal, SyncCommandOrigin::PendingOperations)
+                .await?;
+        }
+    }
+    let mut take_api_client = || {
+        api_client
+            .take()
+            .expect("only commands using the API take the client")
+    };
     match command {
-        None => RunningTimeEntryCommand::execute(get_default_api_client()?).await?,
+        None => RunningTimeEntryCommand::execute(take_api_client()).await?,
         Some(subcommand) => match subcommand {
-            Stop => {
-                StopCommand::execute(&get_default_api_client()?, StopCommandOrigin::CommandLine)
-                    .await?;
+            Stop { time } => {
+                StopCommand::execute(
+                    &take_api_client(),
+                    StopCommandOrigin::CommandLine,
+                    time.resolve()?,
+                )
+                .await?;
             }
-            Continue { interactive } => {
+            Continue { interactive, time } => {
                 let picker = if interactive { Some(picker) } else { None };
-                ContinueCommand::execute(get_default_api_client()?, picker).await?
+                ContinueCommand::execute(take_api_client(), picker, time.resolve()?).await?
+            }
+            Edit {
+                id,
+                current,
+                last,
+                changes,
+            } => {
+                let target = match id {
+                    Some(id) => EditTarget::Id(id),
+                    None if current => EditTarget::Current,
+                    None if last => EditTarget::Last,
+                    None => EditTarget::Picked(picker),
+                };
+                EditCommand::execute(take_api_client(), target, changes).await?
             }
-            List { number } => ListCommand::execute(get_default_api_client()?, number).await?,
-            Current | Running => {
-                RunningTimeEntryCommand::execute(get_default_api_client()?).await?
+            Add(arguments) => {
+                AddCommand::execute(take_api_client(), arguments, args.workspace).await?
             }
-            Start {
-                interactive,
-                billable,
-                description,
-                project,
+            Delete { ids, yes } => {
+                DeleteCommand::execute(take_api_client(), picker, ids, yes).await?
+            }
+            List {
+                number,
+                client,
+                range,
             } => {
-                StartCommand::execute(
-                    get_default_api_client()?,
-                    picker,
-                    description,
-                    project,
-                    billable,
-                    interactive,
+                ListCommand::execute(
+                    take_api_client(),
+                    number,
+                    range.into(),
+                    args.workspace,
+                    client,
                 )
                 .await?
             }
-            Auth { api_token } => {
-                let credentials = Credentials { api_token };
-                let api_client = V9ApiClient::from_credentials(credentials, args.proxy)?;
-                AuthenticationCommand::execute(io::stdout(), api_client, get_storage()).await?
+            Current | Running => RunningTimeEntryCommand::execute(take_api_client()).await?,
+            Start(arguments) => {
+                StartCommand::execute(take_api_client(), picker, arguments, args.workspace).await?
+            }
+            Auth { api_token, email } => {
+                let with_password = email.is_some();
+                let api_client = match email {
+                    Some(email) => {
+                        let password =
+                            utilities::read_password(&format!("Password for {}: ", email))?;
+                        V9ApiClient::from_email_and_password(
+                            &email,
+                            &password,
+                            settings.proxy.clone(),
+                            settings.api_url.clone(),
+                        )?
+                    }
+                    // structopt requires the API token without an email.
+                    None => V9ApiClient::from_credentials(
+                        Credentials {
+                            api_token: api_token.unwrap_or_default(),
+                        },
+                        settings.proxy.clone(),
+                        settings.api_url.clone(),
+                    )?,
+                }
+                .with_retry_policy(settings.retry_policy.clone());
+                AuthenticationCommand::execute(
+                    io::stdout(),
+                    api_client,
+                    get_storage(settings.token_command.clone()),
+                    with_password,
+                )
+                .await?;
+                // Cached projects might belong to a different account.
+                let _ = std::fs::remove_file(config::locate::get_cache_path());
+            }
+            Logout => {
+                LogoutCommand::execute(
+                    get_storage(settings.token_command.clone()),
+                    settings.token_command.is_some(),
+                )
+                .await?;
+                let _ = std::fs::remove_file(config::locate::get_cache_path());
             }
+            Whoami => WhoamiCommand::execute(take_api_client()).await?,
 
             Config {
                 delete,
@@ -115,30 +299,141 @@ async fn execute_subcommand(args: CommandLineArguments) -> ResultWithDefaultErro
                 },
                 None => config::manage::ConfigManageCommand::execute(delete, edit, path).await?,
             },
+            Report { by, client, range } => {
+                ReportCommand::execute(take_api_client(), by, range.into(), args.workspace, client)
+                    .await?
+            }
+            Sync => {
+                SyncCommand::execute(&take_api_client(), &journal, SyncCommandOrigin::CommandLine)
+                    .await?
+            }
+            Cache { cmd } => match cmd {
+                CacheSubCommand::Clear => {
+                    CacheClearCommand::execute(&config::locate::get_cache_path()).await?
+                }
+            },
+            Clients { cmd } => {
+                let api_client = take_api_client();
+                match cmd {
+                    ClientsSubCommand::List => {
+                        ClientsListCommand::execute(api_client, args.workspace).await?
+                    }
+                    ClientsSubCommand::Create { name } => {
+                        ClientsCreateCommand::execute(api_client, args.workspace, name).await?
+                    }
+                    ClientsSubCommand::Rename { name, new_name } => {
+                        ClientsRenameCommand::execute(api_client, args.workspace, name, new_name)
+                            .await?
+                    }
+                    ClientsSubCommand::Archive { name } => {
+                        ClientsArchiveCommand::execute(api_client, args.workspace, name).await?
+                    }
+                }
+            }
+            Projects { cmd } => {
+                let api_client = take_api_client();
+                match cmd {
+                    ProjectsSubCommand::List { client } => {
+                        ProjectsListCommand::execute(api_client, args.workspace, client).await?
+                    }
+                    ProjectsSubCommand::Create { name, client } => {
+                        ProjectsCreateCommand::execute(api_client, args.workspace, name, client)
+                            .await?
+                    }
+                    ProjectsSubCommand::Archive { name } => {
+                        ProjectsArchiveCommand::execute(api_client, args.workspace, name).await?
+                    }
+                    ProjectsSubCommand::Rename { name, new_name } => {
+                        ProjectsRenameCommand::execute(api_client, args.workspace, name, new_name)
+                            .await?
+                    }
+                }
+            }
+            Tasks { cmd } => {
+                let api_client = take_api_client();
+                match cmd {
+                    TasksSubCommand::List { project } => {
+                        TasksListCommand::execute(api_client, args.workspace, project).await?
+                    }
+                    TasksSubCommand::Create { name, project } => {
+                        TasksCreateCommand::execute(api_client, args.workspace, name, project)
+                            .await?
+                    }
+                }
+            }
+            Tags { cmd } => {
+                let api_client = take_api_client();
+                match cmd {
+                    TagsSubCommand::List => {
+                        TagsListCommand::execute(api_client, args.workspace).await?
+                    }
+                    TagsSubCommand::Create { name } => {
+                        TagsCreateCommand::execute(api_client, args.workspace, name).await?
+                    }
+                    TagsSubCommand::Rename { name, new_name } => {
+                        TagsRenameCommand::execute(api_client, args.workspace, name, new_name)
+                            .await?
+                    }
+                    TagsSubCommand::Delete { name, yes } => {
+                        TagsDeleteCommand::execute(api_client, args.workspace, name, yes).await?
+                    }
+                }
+            }
         },
     }
 
     Ok(())
 }
 
-fn get_api_client(proxy: Option<String>) -> ResultWithDefaultError<impl ApiClient> {
-    let credentials_storage = get_storage();
-    return match credentials_storage.read() {
-        Ok(credentials) => V9ApiClient::from_credentials(credentials, proxy),
-        Err(err) => {
-            println!(
-                "{}\n{} {}",
-                "Please set your API token first by calling toggl auth <API_TOKEN>.".red(),
-                "You can find your API token at".blue().bold(),
-                "https://track.toggl.com/profile".blue().bold().underline()
-            );
-            Err(err)
+/// Whether the command parses or prints times, which happens in the timezone of
+/// the Toggl profile unless --tz is given. Other commands skip fetching it.
+fn uses_timezone(command: &Option<Command>) -> bool {
+    let prints_times = matches!(
+        output::get_output_format(),
+        output::OutputFormat::Csv | output::OutputFormat::Tsv
+    );
+    match command {
+        Some(Add(_) | Edit { .. } | List { .. } | Report { .. } | Sync) => true,
+        Some(Start(StartArguments { time, .. }) | Stop { time } | Continue { time, .. }) => {
+            time.at.is_some() || prints_times
         }
-    };
+        None | Some(Current | Running | Delete { .. }) => prints_times,
+        _ => false,
+    }
 }
 
-fn get_storage() -> impl CredentialsStorage {
-    let keyring = Entry::new("togglcli", "default")
-        .unwrap_or_else(|err| panic!("Couldn't create credentials_storage: {err}"));
-    KeyringStorage::new(keyring)
+fn get_api_client(settings: &ApiClientSettings) -> ResultWithDefaultError<impl ApiClient> {
+    let credentials = get_storage(settings.token_command.clone()).read()?;
+    let api_client = V9ApiClient::from_credentials(
+        credentials,
+        settings.proxy.clone(),
+        settings.api_url.clone(),
+    )?
+    .with_retry_policy(settings.retry_policy.clone());
+    let cached_api_client = CachedApiClient::new(
+        api_client,
+        settings.api_url.clone(),
+        config::locate::get_cache_path(),
+        settings.cache_ttl,
+        settings.refresh_cache,
+    );
+    Ok(MemoizedApiClient::new(cached_api_client))
+}
+
+/// Credentials are read from, in order: the TOGGL_API_TOKEN environment
+/// variable, the `token_command` of the global config, the keyring and the
+/// token file in the config directory. `auth` stores the token in the keyring,
+/// or in the token file when there's no keyring.
+fn get_storage(token_command: Option<String>) -> impl CredentialsStorage {
+    let mut storages: Vec<Box<dyn CredentialsStorage>> = vec![
+        Box::new(EnvironmentStorage),
+        Box::new(CommandStorage::new(token_command)),
+    ];
+    if let Ok(keyring) = Entry::new("togglcli", profile::get_profile()) {
+        storages.push(Box::new(KeyringStorage::new(keyring)));
+    }
+    storages.push(Box::new(FileStorage::new(
+        config::locate::get_credentials_path(),
+    )));
+    ChainedStorage::new(storages)
 }
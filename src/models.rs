@@ -1,9 +1,9 @@
 use std::{cmp, env};
 
 use crate::constants;
-use std::collections::HashMap;
+use crate::timezone;
 
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
 use colored::{ColoredString, Colorize};
 use colors_transform::{Color, Rgb};
 use lazy_static::lazy_static;
@@ -11,17 +11,73 @@ use serde::{Deserialize, Serialize};
 
 pub type ResultWithDefaultError<T> = Result<T, Box<dyn std::error::Error>>;
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct Entities {
-    pub time_entries: Vec<TimeEntry>,
-    pub projects: HashMap<i64, Project>,
-    pub tasks: HashMap<i64, Task>,
-    pub clients: HashMap<i64, Client>,
+pub fn format_duration_hmmss(duration: Duration) -> String {
+    format!(
+        "{}:{:02}:{:02}",
+        duration.num_hours(),
+        duration.num_minutes() % 60,
+        duration.num_seconds() % 60
+    )
+}
+
+/// DateRange restricts which time entries are fetched by their start time.
+/// Both ends are optional, an empty range leaves the choice to Toggl, which
+/// only returns recent entries.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DateRange {
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
 }
 
-impl Entities {
-    pub fn running_time_entry(&self) -> Option<TimeEntry> {
-        self.time_entries.iter().find(|te| te.is_running()).cloned()
+impl DateRange {
+    pub fn new(start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>) -> Self {
+        Self { start, end }
+    }
+
+    pub fn today() -> Self {
+        Self::day_of(timezone::now().date_naive())
+    }
+
+    pub fn yesterday() -> Self {
+        Self::day_before(timezone::now().date_naive())
+    }
+
+    pub fn this_week() -> Self {
+        Self::week_of(timezone::now().date_naive())
+    }
+
+    pub fn this_month() -> Self {
+        Self::month_of(timezone::now().date_naive())
+    }
+
+    fn day_of(day: NaiveDate) -> Self {
+        Self::days_from(day, 1)
+    }
+
+    fn day_before(today: NaiveDate) -> Self {
+        Self::day_of(today - Duration::days(1))
+    }
+
+    fn week_of(today: NaiveDate) -> Self {
+        let monday = today - Duration::days(today.weekday().num_days_from_monday().into());
+        Self::days_from(monday, 7)
+    }
+
+    fn month_of(today: NaiveDate) -> Self {
+        let first_day = today.with_day(1).unwrap();
+        let first_day_of_next_month = match today.month() {
+            12 => NaiveDate::from_ymd_opt(today.year() + 1, 1, 1),
+            month => NaiveDate::from_ymd_opt(today.year(), month + 1, 1),
+        }
+        .unwrap();
+        Self::days_from(first_day, (first_day_of_next_month - first_day).num_days())
+    }
+
+    fn days_from(first_day: NaiveDate, days: i64) -> Self {
+        Self {
+            start: Some(timezone::start_of_day(first_day)),
+            end: Some(timezone::start_of_day(first_day + Duration::days(days))),
+        }
     }
 }
 
@@ -132,6 +188,25 @@ impl Project {
     }
 }
 
+#[cfg(test)]
+impl Project {
+    /// An active project without a client in workspace 1, shared by the tests.
+    pub fn fixture(id: i64, name: &str) -> Self {
+        Project {
+            id,
+            name: name.to_string(),
+            workspace_id: 1,
+            client: None,
+            is_private: false,
+            active: true,
+            at: Utc::now(),
+            created_at: Utc::now(),
+            color: "#06aaf5".to_string(),
+            billable: None,
+        }
+    }
+}
+
 impl std::fmt::Display for Project {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -155,11 +230,38 @@ impl std::fmt::Display for Project {
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Workspace {
+    pub id: i64,
+    pub name: String,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Client {
     pub id: i64,
     pub name: String,
     pub workspace_id: i64,
+    #[serde(default)]
+    pub archived: bool,
+}
+
+impl std::fmt::Display for Client {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Tag {
+    pub id: i64,
+    pub name: String,
+    pub workspace_id: i64,
+}
+
+impl std::fmt::Display for Tag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -170,6 +272,12 @@ pub struct Task {
     pub project: Project,
 }
 
+impl std::fmt::Display for Task {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.project, self.name)
+    }
+}
+
 impl TimeEntry {
     pub fn get_description(&self) -> String {
         match self.description.as_ref() {
@@ -186,13 +294,7 @@ impl TimeEntry {
     }
 
     pub fn get_duration_hmmss(&self) -> String {
-        let duration = self.get_duration();
-        format!(
-            "{}:{:02}:{:02}",
-            duration.num_hours(),
-            duration.num_minutes() % 60,
-            duration.num_seconds() % 60
-        )
+        format_duration_hmmss(self.get_duration())
     }
 
     pub fn is_running(&self) -> bool {
@@ -275,3 +377,48 @@ impl std::fmt::Display for TimeEntry {
         write!(f, "{}", summary)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn days(first_day: NaiveDate, end: NaiveDate) -> DateRange {
+        DateRange::new(
+            Some(timezone::start_of_day(first_day)),
+            Some(timezone::start_of_day(end)),
+        )
+    }
+
+    #[test]
+    fn yesterday_spans_the_previous_day_across_the_new_year() {
+        assert_eq!(
+            DateRange::day_before(date(2024, 1, 1)),
+            days(date(2023, 12, 31), date(2024, 1, 1))
+        );
+    }
+
+    #[test]
+    fn weeks_start_on_monday() {
+        // 2023-01-01 is a Sunday, the last day of the week starting 2022-12-26.
+        assert_eq!(
+            DateRange::week_of(date(2023, 1, 1)),
+            days(date(2022, 12, 26), date(2023, 1, 2))
+        );
+        assert_eq!(
+            DateRange::week_of(date(2023, 1, 2)),
+            days(date(2023, 1, 2), date(2023, 1, 9))
+        );
+    }
+
+    #[test]
+    fn december_ends_with_the_first_of_january() {
+        assert_eq!(
+            DateRange::month_of(date(2023, 12, 15)),
+            days(date(2023, 12, 1), date(2024, 1, 1))
+        );
+    }
+}
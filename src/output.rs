@@ -0,0 +1,300 @@
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use serde::Serialize;
+
+use crate::config::model::BranchConfig;
+use crate::models::{Client, Project, Tag, Task, TimeEntry};
+use crate::timezone;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Csv,
+    Tsv,
+}
+
+impl OutputFormat {
+    pub const VARIANTS: [&'static str; 4] = ["text", "json", "csv", "tsv"];
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            "tsv" => Ok(OutputFormat::Tsv),
+            _ => Err(format!("Unknown output format {}", value)),
+        }
+    }
+}
+
+static OUTPUT_FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+pub fn set_output_format(format: OutputFormat) {
+    let _ = OUTPUT_FORMAT.set(format);
+}
+
+pub fn get_output_format() -> OutputFormat {
+    *OUTPUT_FORMAT.get_or_init(|| OutputFormat::Text)
+}
+
+/// Tabular describes how an item is flattened into a CSV or TSV row.
+pub trait Tabular {
+    fn headers() -> Vec<&'static str>;
+    fn fields(&self) -> Vec<String>;
+}
+
+/// Prints a status message. Only text output goes to stdout, so that the other
+/// formats can be piped into other programs.
+pub fn print_message(message: impl Display) {
+    match get_output_format() {
+        OutputFormat::Text => println!("{}", message),
+        _ => eprintln!("{}", message),
+    }
+}
+
+/// Prints a single item, JSON prints `null` and CSV/TSV only the headers without one.
+pub fn print_item<T: Display + Serialize + Tabular>(item: Option<&T>) {
+    match get_output_format() {
+        OutputFormat::Text => {
+            if let Some(item) = item {
+                println!("{}", item)
+            }
+        }
+        OutputFormat::Json => println!("{}", to_json(&item)),
+        format => print_table(format, item.into_iter()),
+    }
+}
+
+pub fn print_items<'a, T: Display + Serialize + Tabular + 'a>(
+    items: impl IntoIterator<Item = &'a T>,
+) {
+    match get_output_format() {
+        OutputFormat::Text => items.into_iter().for_each(|item| println!("{}", item)),
+        OutputFormat::Json => println!("{}", to_json(&items.into_iter().collect::<Vec<_>>())),
+        format => print_table(format, items.into_iter()),
+    }
+}
+
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string_pretty(value).expect("models are always serializable")
+}
+
+fn print_table<'a, T: Tabular + 'a>(format: OutputFormat, items: impl Iterator<Item = &'a T>) {
+    let format_row = |fields: Vec<String>| match format {
+        OutputFormat::Tsv => fields
+            .iter()
+            .map(|field| field.replace(['\t', '\n', '\r'], " "))
+            .collect::<Vec<_>>()
+            .join("\t"),
+        _ => fields
+            .iter()
+            .map(|field| escape_csv_field(field))
+            .collect::<Vec<_>>()
+            .join(","),
+    };
+    println!(
+        "{}",
+        format_row(T::headers().into_iter().map(String::from).collect())
+    );
+    for item in items {
+        println!("{}", format_row(item.fields()));
+    }
+}
+
+fn escape_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+impl Tabular for TimeEntry {
+    fn headers() -> Vec<&'static str> {
+        vec![
+            "id",
+            "description",
+            "start",
+            "stop",
+            "duration",
+            "billable",
+            "project",
+            "client",
+            "task",
+            "tags",
+            "workspace_id",
+        ]
+    }
+
+    fn fields(&self) -> Vec<String> {
+        let format_time = |time: chrono::DateTime<chrono::Utc>| {
+            time.with_timezone(&timezone::get_timezone()).to_rfc3339()
+        };
+        vec![
+            self.id.to_string(),
+            self.description.clone(),
+            format_time(self.start),
+            self.stop.map(format_time).unwrap_or_default(),
+            self.get_duration().num_seconds().to_string(),
+            self.billable.to_string(),
+            self.project
+                .as_ref()
+                .map(|p| p.name.clone())
+                .unwrap_or_default(),
+            self.project
+                .as_ref()
+                .and_then(|p| p.client.as_ref())
+                .map(|c| c.name.clone())
+                .unwrap_or_default(),
+            self.task
+                .as_ref()
+                .map(|t| t.name.clone())
+                .unwrap_or_default(),
+            self.tags.join(","),
+            self.workspace_id.to_string(),
+        ]
+    }
+}
+
+impl Tabular for BranchConfig {
+    fn headers() -> Vec<&'static str> {
+        vec![
+            "profile",
+            "workspace",
+            "description",
+            "project",
+            "client",
+            "create_project",
+            "task",
+            "tags",
+            "billable",
+        ]
+    }
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.profile.clone().unwrap_or_default(),
+            self.workspace.clone().unwrap_or_default(),
+            self.description.clone().unwrap_or_default(),
+            self.project.clone().unwrap_or_default(),
+            self.client.clone().unwrap_or_default(),
+            self.create_project.to_string(),
+            self.task.clone().unwrap_or_default(),
+            self.tags.clone().unwrap_or_default().join(","),
+            self.billable.to_string(),
+        ]
+    }
+}
+
+impl Tabular for Project {
+    fn headers() -> Vec<&'static str> {
+        vec![
+            "id",
+            "name",
+            "client",
+            "active",
+            "billable",
+            "color",
+            "workspace_id",
+        ]
+    }
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.name.clone(),
+            self.client
+                .as_ref()
+                .map(|c| c.name.clone())
+                .unwrap_or_default(),
+            self.active.to_string(),
+            self.billable.unwrap_or_default().to_string(),
+            self.color.clone(),
+            self.workspace_id.to_string(),
+        ]
+    }
+}
+
+impl Tabular for Client {
+    fn headers() -> Vec<&'static str> {
+        vec!["id", "name", "archived", "workspace_id"]
+    }
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.name.clone(),
+            self.archived.to_string(),
+            self.workspace_id.to_string(),
+        ]
+    }
+}
+
+impl Tabular for Task {
+    fn headers() -> Vec<&'static str> {
+        vec!["id", "name", "project", "client", "workspace_id"]
+    }
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.name.clone(),
+            self.project.name.clone(),
+            self.project
+                .client
+                .as_ref()
+                .map(|c| c.name.clone())
+                .unwrap_or_default(),
+            self.workspace_id.to_string(),
+        ]
+    }
+}
+
+impl Tabular for Tag {
+    fn headers() -> Vec<&'static str> {
+        vec!["id", "name", "workspace_id"]
+    }
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.name.clone(),
+            self.workspace_id.to_string(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_fields_are_quoted_only_when_needed() {
+        assert_eq!(escape_csv_field("Coding"), "Coding");
+        assert_eq!(escape_csv_field("Review, part 2"), "\"Review, part 2\"");
+        assert_eq!(
+            escape_csv_field("The \"fun\" bug"),
+            "\"The \"\"fun\"\" bug\""
+        );
+    }
+
+    #[test]
+    fn time_entry_fields_match_the_headers() {
+        let time_entry = TimeEntry {
+            tags: vec!["a".to_string(), "b".to_string()],
+            ..TimeEntry::default()
+        };
+
+        let fields = time_entry.fields();
+
+        assert_eq!(fields.len(), TimeEntry::headers().len());
+        assert_eq!(fields[9], "a,b");
+    }
+}
@@ -28,44 +28,56 @@ fn create_element_hash_map(items: &[PickableItem]) -> HashMap<String, PickableIt
         .collect()
 }
 
-impl ItemPicker for FzfPicker {
-    fn pick(&self, items: Vec<PickableItem>) -> ResultWithDefaultError<PickableItemKey> {
-        let mut command = Command::new("fzf");
-        command
-            .arg("-n2..")
-            .arg("--ansi")
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped());
+fn run_fzf(items: Vec<PickableItem>, multi: bool) -> ResultWithDefaultError<Vec<PickableItemKey>> {
+    let mut command = Command::new("fzf");
+    command.arg("-n2..").arg("--ansi");
+    if multi {
+        command.arg("--multi");
+    }
+    command.stdin(Stdio::piped()).stdout(Stdio::piped());
 
-        match command.spawn() {
-            Ok(mut child) => {
-                let fzf_input = format_as_fzf_input(&items);
-                let possible_elements = create_element_hash_map(&items);
+    match command.spawn() {
+        Ok(mut child) => {
+            let fzf_input = format_as_fzf_input(&items);
+            let possible_elements = create_element_hash_map(&items);
 
-                writeln!(child.stdin.as_mut().unwrap(), "{}", fzf_input)?;
+            writeln!(child.stdin.as_mut().unwrap(), "{}", fzf_input)?;
 
-                match child.wait_with_output() {
-                    Err(_) => Err(Box::new(PickerError::Generic)),
-                    Ok(output) => match output.status.code() {
-                        Some(0) => {
-                            let user_selected_string = String::from_utf8(output.stdout)?;
-                            let selected_item_index =
-                                utilities::remove_trailing_newline(user_selected_string);
-                            let selected_item =
-                                possible_elements.get(&selected_item_index).unwrap();
-                            Ok(selected_item.clone())
-                        }
-                        // This is copied from zoxide's fzf handler.
-                        // https://github.com/rohankumardubey/zoxide/blob/main/src/util.rs
-                        Some(128..=254) | None => Err(Box::new(PickerError::Cancelled)),
-                        _ => Err(Box::new(PickerError::Generic)),
-                    },
-                }
-            }
-            Err(e) if e.kind() == io::ErrorKind::NotFound => {
-                Err(Box::new(PickerError::FzfNotInstalled))
+            match child.wait_with_output() {
+                Err(_) => Err(Box::new(PickerError::Generic)),
+                Ok(output) => match output.status.code() {
+                    Some(0) => {
+                        let user_selected_string = String::from_utf8(output.stdout)?;
+                        let selected_items =
+                            utilities::remove_trailing_newline(user_selected_string)
+                                .lines()
+                                .map(|line| possible_elements.get(line).unwrap().clone())
+                                .collect();
+                        Ok(selected_items)
+                    }
+                    // This is copied from zoxide's fzf handler.
+                    // https://github.com/rohankumardubey/zoxide/blob/main/src/util.rs
+                    Some(128..=254) | None => Err(Box::new(PickerError::Cancelled)),
+                    _ => Err(Box::new(PickerError::Generic)),
+                },
             }
-            Err(_) => Err(Box::new(PickerError::Generic)),
         }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            Err(Box::new(PickerError::FzfNotInstalled))
+        }
+        Err(_) => Err(Box::new(PickerError::Generic)),
+    }
+}
+
+impl ItemPicker for FzfPicker {
+    fn pick(&self, items: Vec<PickableItem>) -> ResultWithDefaultError<PickableItemKey> {
+        match run_fzf(items, false)?.first() {
+            None => Err(Box::new(PickerError::Generic)),
+            Some(key) => Ok(key.clone()),
+        }
+    }
+
+    fn pick_many(&self, items: Vec<PickableItem>) -> ResultWithDefaultError<Vec<PickableItemKey>> {
+        run_fzf(items, true)
     }
 }
@@ -9,6 +9,7 @@ use std::str::FromStr;
 use crate::constants;
 use crate::models;
 use crate::models::Project;
+use crate::models::Tag;
 use crate::models::Task;
 use models::{ResultWithDefaultError, TimeEntry};
 
@@ -23,6 +24,7 @@ pub enum PickableItemKind {
     TimeEntry,
     Project,
     Task,
+    Tag,
 }
 
 pub struct PickableItem {
@@ -40,6 +42,7 @@ impl FromStr for PickableItemKey {
             "TimeEntry" => PickableItemKind::TimeEntry,
             "Project" => PickableItemKind::Project,
             "Task" => PickableItemKind::Task,
+            "Tag" => PickableItemKind::Tag,
             _ => return Err(()),
         };
 
@@ -61,6 +64,7 @@ impl Display for PickableItemKey {
                 PickableItemKind::TimeEntry => "TimeEntry",
                 PickableItemKind::Project => "Project",
                 PickableItemKind::Task => "Task",
+                PickableItemKind::Tag => "Tag",
             },
             self.id
         )
@@ -127,10 +131,21 @@ impl PickableItem {
             formatted: formatted_task,
         }
     }
+
+    pub fn from_tag(tag: Tag) -> PickableItem {
+        PickableItem {
+            key: PickableItemKey {
+                id: tag.id,
+                kind: PickableItemKind::Tag,
+            },
+            formatted: tag.name,
+        }
+    }
 }
 
 pub trait ItemPicker {
     fn pick(&self, items: Vec<PickableItem>) -> ResultWithDefaultError<PickableItemKey>;
+    fn pick_many(&self, items: Vec<PickableItem>) -> ResultWithDefaultError<Vec<PickableItemKey>>;
 }
 
 #[cfg(unix)]
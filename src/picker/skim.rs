@@ -8,12 +8,15 @@ use skim::prelude::*;
 
 pub struct SkimPicker;
 
-fn get_skim_configuration(items: Vec<PickableItem>) -> (SkimOptions<'static>, SkimItemReceiver) {
+fn get_skim_configuration(
+    items: Vec<PickableItem>,
+    multi: bool,
+) -> (SkimOptions<'static>, SkimItemReceiver) {
     let options = SkimOptionsBuilder::default()
         // Set viewport to take entire screen
         .height(Some("100%"))
-        // Disable multiselect
-        .multi(false)
+        // Select several items with Tab when enabled
+        .multi(multi)
         .build()
         .unwrap();
 
@@ -37,31 +40,41 @@ impl SkimItem for PickableItem {
     }
 }
 
-impl ItemPicker for SkimPicker {
-    fn pick(&self, items: Vec<PickableItem>) -> ResultWithDefaultError<PickableItemKey> {
-        let (options, source) = get_skim_configuration(items);
-        let output = Skim::run_with(&options, Some(source));
+fn run_skim(items: Vec<PickableItem>, multi: bool) -> ResultWithDefaultError<Vec<PickableItemKey>> {
+    let (options, source) = get_skim_configuration(items, multi);
+    let output = Skim::run_with(&options, Some(source));
 
-        match output {
-            None => Err(Box::new(PickerError::Cancelled)),
-            Some(item) => {
-                if item.is_abort {
-                    Err(Box::new(PickerError::Cancelled))
-                } else {
-                    let selectable_items = item
-                        .selected_items
-                        .iter()
-                        .map(|selected_items| {
-                            selected_items.output().parse::<PickableItemKey>().unwrap()
-                        })
-                        .collect::<Vec<PickableItemKey>>();
+    match output {
+        None => Err(Box::new(PickerError::Cancelled)),
+        Some(item) => {
+            if item.is_abort {
+                Err(Box::new(PickerError::Cancelled))
+            } else {
+                let selectable_items = item
+                    .selected_items
+                    .iter()
+                    .map(|selected_items| {
+                        selected_items.output().parse::<PickableItemKey>().unwrap()
+                    })
+                    .collect::<Vec<PickableItemKey>>();
 
-                    match selectable_items.first() {
-                        None => Err(Box::new(PickerError::Generic)),
-                        Some(id) => Ok(id.clone()),
-                    }
+                if selectable_items.is_empty() {
+                    Err(Box::new(PickerError::Generic))
+                } else {
+                    Ok(selectable_items)
                 }
             }
         }
     }
 }
+
+impl ItemPicker for SkimPicker {
+    fn pick(&self, items: Vec<PickableItem>) -> ResultWithDefaultError<PickableItemKey> {
+        let selectable_items = run_skim(items, false)?;
+        Ok(selectable_items[0].clone())
+    }
+
+    fn pick_many(&self, items: Vec<PickableItem>) -> ResultWithDefaultError<Vec<PickableItemKey>> {
+        run_skim(items, true)
+    }
+}
@@ -0,0 +1,81 @@
+use std::sync::OnceLock;
+
+use crate::config;
+use crate::constants;
+use crate::error::ArgumentError;
+
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Sets the profile whose credentials, cache and journal are used. It can only
+/// be set once, before it's first used.
+pub fn set_profile(profile: String) {
+    let _ = PROFILE.set(profile);
+}
+
+/// The profile set with `set_profile`, or the default one if it wasn't set.
+pub fn get_profile() -> &'static str {
+    PROFILE.get_or_init(|| constants::DEFAULT_PROFILE.to_string())
+}
+
+pub fn is_default_profile() -> bool {
+    get_profile() == constants::DEFAULT_PROFILE
+}
+
+/// The command that stores the API token of the current profile.
+pub fn get_auth_command() -> String {
+    if is_default_profile() {
+        "toggl auth <API_TOKEN>".to_string()
+    } else {
+        format!("toggl auth --profile {} <API_TOKEN>", get_profile())
+    }
+}
+
+/// Profile names end up in file names, so they're limited to letters, digits,
+/// dashes and underscores.
+pub fn parse_profile(name: &str) -> Result<String, ArgumentError> {
+    let is_valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if is_valid {
+        Ok(name.to_string())
+    } else {
+        Err(ArgumentError::InvalidProfile(name.to_string()))
+    }
+}
+
+/// The profile given on the command line or in TOGGL_PROFILE, else the one
+/// pinned by the active config of the current directory, else the default one.
+pub fn resolve_profile(profile: Option<String>) -> Result<String, ArgumentError> {
+    match profile.or_else(get_config_profile) {
+        Some(profile) => parse_profile(&profile),
+        None => Ok(constants::DEFAULT_PROFILE.to_string()),
+    }
+}
+
+fn get_config_profile() -> Option<String> {
+    // A missing or broken config shouldn't keep commands like `config --edit` from working.
+    let config_path = config::locate::locate_config_path().ok()?;
+    let track_config = config::parser::get_config_from_file(config_path).ok()?;
+    track_config
+        .get_active_config()
+        .map(|config| config.profile.clone())
+        .ok()?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_names_are_limited_to_file_name_safe_characters() {
+        assert_eq!(parse_profile("work_2-eu").unwrap(), "work_2-eu");
+        for name in ["", "../work", "my work", "work.json"] {
+            assert!(
+                matches!(parse_profile(name), Err(ArgumentError::InvalidProfile(_))),
+                "{} should be rejected",
+                name
+            );
+        }
+    }
+}
@@ -0,0 +1,285 @@
+use std::str::FromStr;
+
+use chrono::{
+    DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday,
+};
+
+use crate::error::ArgumentError;
+use crate::timezone;
+
+/// TimeExpression is a time given on the command line. Its syntax is checked
+/// while the arguments are parsed, but it's only resolved once the timezone
+/// it should be interpreted in is known.
+#[derive(Debug, Clone)]
+pub struct TimeExpression(String);
+
+impl FromStr for TimeExpression {
+    type Err = ArgumentError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        parse_datetime(value, &Utc::now())?;
+        Ok(TimeExpression(value.to_string()))
+    }
+}
+
+impl TimeExpression {
+    pub fn resolve(&self) -> DateTime<Utc> {
+        // Whether an expression parses doesn't depend on the timezone.
+        parse_datetime(&self.0, &timezone::now()).expect("time expressions are checked when parsed")
+    }
+}
+
+/// RangeExpression is a `START..END` pair of time expressions, where the end
+/// is exclusive, e.g. `monday..tomorrow` for this week up to and including
+/// today, or `2023-01-01..2023-02-01` for January.
+#[derive(Debug, Clone)]
+pub struct RangeExpression {
+    pub start: TimeExpression,
+    pub end: TimeExpression,
+}
+
+impl FromStr for RangeExpression {
+    type Err = ArgumentError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.split_once("..") {
+            Some((start, end)) => Ok(RangeExpression {
+                start: start.parse()?,
+                end: end.parse()?,
+            }),
+            None => Err(ArgumentError::InvalidRange(value.to_string())),
+        }
+    }
+}
+
+/// Parses durations such as `1h30m`, `90m`, `1.5h` or `45s`.
+pub fn parse_duration(value: &str) -> Result<Duration, ArgumentError> {
+    let invalid = || ArgumentError::InvalidDuration(value.to_string());
+    let mut seconds = 0.0;
+    let mut number = String::new();
+    for c in value.chars().filter(|c| !c.is_whitespace()) {
+        let unit = match c {
+            '0'..='9' | '.' => {
+                number.push(c);
+                continue;
+            }
+            'h' => 3600.0,
+            'm' => 60.0,
+            's' => 1.0,
+            _ => return Err(invalid()),
+        };
+        seconds += number.parse::<f64>().map_err(|_| invalid())? * unit;
+        number.clear();
+    }
+    // Durations beyond what chrono can represent would panic when converted.
+    let max_seconds = Duration::max_value().num_seconds() as f64;
+    if !number.is_empty() || !(1.0..=max_seconds).contains(&seconds) {
+        return Err(invalid());
+    }
+    Ok(Duration::seconds(seconds.round() as i64))
+}
+
+/// Parses a point in time relative to `now`, interpreting wall clock times in
+/// the timezone of `now`. Accepted expressions are RFC 3339 times, `now`, an
+/// optional day followed by an optional `HH:MM` time, where the day is one of
+/// `today`, `yesterday`, `tomorrow`, `YYYY-MM-DD`, a weekday such as `monday`
+/// (the most recent one, including today) or `last monday` (before today).
+/// Days without a time refer to their midnight, a time without a day to today.
+pub fn parse_datetime<Tz: TimeZone>(
+    value: &str,
+    now: &DateTime<Tz>,
+) -> Result<DateTime<Utc>, ArgumentError> {
+    let value = value.trim();
+    let invalid = || ArgumentError::InvalidTime(value.to_string());
+    if value.eq_ignore_ascii_case("now") {
+        return Ok(now.with_timezone(&Utc));
+    }
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Ok(datetime.with_timezone(&Utc));
+    }
+
+    let mut words: Vec<String> = value.split_whitespace().map(str::to_lowercase).collect();
+    let time = match words.last().and_then(|word| parse_time(word)) {
+        Some(time) => {
+            words.pop();
+            Some(time)
+        }
+        None => None,
+    };
+    let today = now.date_naive();
+    let day = match words.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        [] if time.is_some() => today,
+        ["today"] => today,
+        ["yesterday"] => today - Duration::days(1),
+        ["tomorrow"] => today + Duration::days(1),
+        ["last", weekday] => last_weekday(today, parse_weekday(weekday).ok_or_else(invalid)?, 1),
+        [word] => match NaiveDate::parse_from_str(word, "%Y-%m-%d") {
+            Ok(date) => date,
+            Err(_) => last_weekday(today, parse_weekday(word).ok_or_else(invalid)?, 0),
+        },
+        _ => return Err(invalid()),
+    };
+
+    Ok(to_utc(
+        &now.timezone(),
+        day.and_time(time.unwrap_or(NaiveTime::MIN)),
+    ))
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value, "%H:%M").ok()
+}
+
+fn parse_weekday(value: &str) -> Option<Weekday> {
+    // Weekday's FromStr accepts both full and abbreviated English names.
+    value.parse::<Weekday>().ok()
+}
+
+/// The most recent `weekday` at least `min_days_ago` days before `today`.
+fn last_weekday(today: NaiveDate, weekday: Weekday, min_days_ago: i64) -> NaiveDate {
+    let days_ago = (today.weekday().num_days_from_monday() as i64
+        - weekday.num_days_from_monday() as i64)
+        .rem_euclid(7);
+    let days_ago = if days_ago < min_days_ago {
+        days_ago + 7
+    } else {
+        days_ago
+    };
+    today - Duration::days(days_ago)
+}
+
+pub fn to_utc<Tz: TimeZone>(timezone: &Tz, datetime: NaiveDateTime) -> DateTime<Utc> {
+    // Wall clock times skipped by a DST transition are moved past the gap.
+    timezone
+        .from_local_datetime(&datetime)
+        .earliest()
+        .or_else(|| {
+            timezone
+                .from_local_datetime(&(datetime + Duration::hours(1)))
+                .earliest()
+        })
+        .map(|datetime| datetime.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&datetime))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    // Wednesday, 15 March 2023 at 12:30 in UTC+02:00.
+    fn now() -> DateTime<FixedOffset> {
+        FixedOffset::east_opt(2 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2023, 3, 15, 12, 30, 0)
+            .unwrap()
+    }
+
+    fn utc(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn parse(value: &str) -> DateTime<Utc> {
+        parse_datetime(value, &now()).unwrap()
+    }
+
+    #[test]
+    fn durations_combine_units() {
+        assert_eq!(parse_duration("1h30m").unwrap(), Duration::minutes(90));
+        assert_eq!(parse_duration("1h 30m").unwrap(), Duration::minutes(90));
+        assert_eq!(parse_duration("2m30s").unwrap(), Duration::seconds(150));
+    }
+
+    #[test]
+    fn durations_accept_a_single_unit() {
+        assert_eq!(parse_duration("90m").unwrap(), Duration::minutes(90));
+        assert_eq!(parse_duration("45s").unwrap(), Duration::seconds(45));
+        assert_eq!(parse_duration("2h").unwrap(), Duration::hours(2));
+    }
+
+    #[test]
+    fn durations_accept_fractions() {
+        assert_eq!(parse_duration("1.5h").unwrap(), Duration::minutes(90));
+        assert_eq!(parse_duration(".5m").unwrap(), Duration::seconds(30));
+    }
+
+    #[test]
+    fn invalid_durations_are_rejected() {
+        for value in [
+            "",
+            "90",
+            "h",
+            "1x",
+            "1.2.3h",
+            "0m",
+            "-5m",
+            "1h30",
+            "99999999999999999h",
+        ] {
+            assert!(
+                matches!(
+                    parse_duration(value),
+                    Err(ArgumentError::InvalidDuration(_))
+                ),
+                "{} should be rejected",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn times_refer_to_today_in_the_given_timezone() {
+        assert_eq!(parse("09:00"), utc(2023, 3, 15, 7, 0));
+        assert_eq!(parse("today 23:15"), utc(2023, 3, 15, 21, 15));
+    }
+
+    #[test]
+    fn relative_days_accept_an_optional_time() {
+        assert_eq!(parse("yesterday 14:00"), utc(2023, 3, 14, 12, 0));
+        assert_eq!(parse("yesterday"), utc(2023, 3, 13, 22, 0));
+        assert_eq!(parse("Tomorrow 08:00"), utc(2023, 3, 16, 6, 0));
+    }
+
+    #[test]
+    fn weekdays_refer_to_the_most_recent_one() {
+        assert_eq!(parse("monday"), utc(2023, 3, 12, 22, 0));
+        assert_eq!(parse("wed 10:00"), utc(2023, 3, 15, 8, 0));
+        assert_eq!(parse("thursday"), utc(2023, 3, 8, 22, 0));
+    }
+
+    #[test]
+    fn last_weekday_is_before_today() {
+        assert_eq!(parse("last monday"), utc(2023, 3, 12, 22, 0));
+        assert_eq!(parse("last wednesday 17:00"), utc(2023, 3, 8, 15, 0));
+    }
+
+    #[test]
+    fn absolute_dates_and_times_are_accepted() {
+        assert_eq!(parse("2023-01-02"), utc(2023, 1, 1, 22, 0));
+        assert_eq!(parse("2023-01-02 09:45"), utc(2023, 1, 2, 7, 45));
+        assert_eq!(parse("2023-01-02T09:45:00Z"), utc(2023, 1, 2, 9, 45));
+        assert_eq!(parse("now"), utc(2023, 3, 15, 10, 30));
+    }
+
+    #[test]
+    fn invalid_times_are_rejected() {
+        for value in [
+            "",
+            "25:00",
+            "someday",
+            "last",
+            "last year",
+            "yesterday noon",
+        ] {
+            assert!(
+                matches!(
+                    parse_datetime(value, &now()),
+                    Err(ArgumentError::InvalidTime(_))
+                ),
+                "{} should be rejected",
+                value
+            );
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use std::sync::OnceLock;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+
+use crate::api::client::ApiClient;
+use crate::error::{self, ArgumentError};
+use crate::models::ResultWithDefaultError;
+use crate::time_parser;
+
+static TIMEZONE: OnceLock<Tz> = OnceLock::new();
+
+/// Sets the timezone used to display times, interpret time expressions and
+/// decide where days start. It can only be set once, before it's first used.
+pub fn set_timezone(timezone: Tz) {
+    let _ = TIMEZONE.set(timezone);
+}
+
+/// The timezone set with `set_timezone`, or the local one if it wasn't set.
+pub fn get_timezone() -> Tz {
+    *TIMEZONE.get_or_init(get_local_timezone)
+}
+
+pub fn now() -> DateTime<Tz> {
+    Utc::now().with_timezone(&get_timezone())
+}
+
+pub fn to_utc(datetime: NaiveDateTime) -> DateTime<Utc> {
+    time_parser::to_utc(&get_timezone(), datetime)
+}
+
+pub fn start_of_day(day: NaiveDate) -> DateTime<Utc> {
+    to_utc(day.and_hms_opt(0, 0, 0).unwrap())
+}
+
+pub fn parse_timezone(name: &str) -> Result<Tz, ArgumentError> {
+    name.parse::<Tz>()
+        .map_err(|_| ArgumentError::InvalidTimezone(name.to_string()))
+}
+
+pub fn get_local_timezone() -> Tz {
+    iana_time_zone::get_timezone()
+        .ok()
+        .and_then(|name| name.parse::<Tz>().ok())
+        .unwrap_or(Tz::UTC)
+}
+
+/// The timezone of the user's Toggl profile. Falls back to the local timezone
+/// when Toggl can't be reached, so that commands keep working offline.
+pub async fn get_profile_timezone(api_client: &impl ApiClient) -> ResultWithDefaultError<Tz> {
+    let user = api_client.get_user().await;
+    match user {
+        Ok(user) => Ok(user
+            .timezone
+            .parse::<Tz>()
+            .unwrap_or_else(|_| get_local_timezone())),
+        Err(error) if error::is_network_error(error.as_ref()) => Ok(get_local_timezone()),
+        Err(error) => Err(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::client::MockApiClient;
+    use crate::error::ApiError;
+
+    #[tokio::test]
+    async fn the_local_timezone_is_only_used_while_offline() {
+        let mut api_client = MockApiClient::new();
+        api_client
+            .expect_get_user()
+            .returning(|| Err(Box::new(ApiError::Network)));
+
+        let timezone = get_profile_timezone(&api_client).await;
+
+        assert_eq!(timezone.unwrap(), get_local_timezone());
+    }
+
+    #[tokio::test]
+    async fn rejected_api_tokens_are_reported() {
+        let mut api_client = MockApiClient::new();
+        api_client
+            .expect_get_user()
+            .returning(|| Err(Box::new(ApiError::Unauthorized("".to_string()))));
+
+        let error = get_profile_timezone(&api_client).await.unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<ApiError>(),
+            Some(ApiError::Unauthorized(_))
+        ));
+    }
+}
@@ -21,6 +21,22 @@ pub fn read_from_stdin(text: &str) -> String {
     remove_trailing_newline(result)
 }
 
+/// Prompts for a password without echoing it. Without a terminal, e.g. when the
+/// password is piped in, it's read from stdin instead.
+pub fn read_password(text: &str) -> io::Result<String> {
+    if !io::stdin().is_terminal() {
+        return read_password_line(io::stdin().lock());
+    }
+    rpassword::prompt_password(text)
+}
+
+fn read_password_line(mut reader: impl io::BufRead) -> io::Result<String> {
+    let mut result = String::new();
+    reader.read_line(&mut result)?;
+    // Unlike other input, whitespace is a valid part of a password.
+    Ok(result.trim_end_matches(['\r', '\n']).to_string())
+}
+
 pub fn simplify_config_path_for_display(dir: &Path) -> String {
     if !std::io::stdout().is_terminal() {
         return dir.display().to_string();
@@ -97,3 +113,15 @@ fn print_without_buffer(text: &str) {
     print!("{}", text);
     io::stdout().flush().unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn piped_passwords_keep_their_whitespace() {
+        let password = read_password_line(" p w \r\nsecond line\n".as_bytes());
+
+        assert_eq!(password.unwrap(), " p w ");
+    }
+}